@@ -0,0 +1,38 @@
+use crate::{Error, Result};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+pub struct ImageDownload {
+    response: reqwest::Response,
+}
+
+impl ImageDownload {
+    pub(crate) fn new(response: reqwest::Response) -> Self {
+        Self { response }
+    }
+
+    pub fn content_length(&self) -> Option<u64> {
+        self.response.content_length()
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+    }
+
+    pub async fn write_to<W, F>(mut self, writer: &mut W, mut progress: F) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+        F: FnMut(u64),
+    {
+        let mut written = 0;
+        while let Some(chunk) = self.response.chunk().await.map_err(Error::Http)? {
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+            progress(written);
+        }
+        writer.flush().await?;
+        Ok(written)
+    }
+}
@@ -1,6 +1,6 @@
-use crate::{MarkAs, WithStatus};
+use crate::{Failure, MarkAs, WithStatus};
 
-use super::Prompt;
+use super::{websocket::ExecutionError, Prompt};
 use chrono::{serde::ts_milliseconds, DateTime, Utc};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
@@ -112,9 +112,10 @@ impl IntoIterator for History {
 
 impl From<HistoryLogEntry> for PromptBatchEntry {
     fn from(entry: HistoryLogEntry) -> Self {
-        if entry
-            .status
-            .messages
+        let messages = &entry.status.messages;
+        if let Some(failure) = entry.failure() {
+            entry.prompt.mark_as(crate::Status::Failed(failure))
+        } else if messages
             .iter()
             .any(|msg| msg.kind == MessageKind::Interruped)
         {
@@ -127,10 +128,93 @@ impl From<HistoryLogEntry> for PromptBatchEntry {
     }
 }
 
+impl HistoryLogEntry {
+    /// Retrieves the details of the execution error of the prompt, which
+    /// history stores as an "execution_error" message.
+    pub fn execution_error(&self) -> crate::Result<Option<ExecutionError>> {
+        let message = self
+            .status
+            .messages
+            .iter()
+            .find(|msg| msg.kind == MessageKind::Error);
+        let Some(message) = message else {
+            return Ok(None);
+        };
+        let mut data = serde_json::Value::from_iter(message.data.other.clone());
+        data["prompt_id"] = message.data.prompt_id.clone().into();
+        data["timestamp"] = message.data.timestamp.timestamp_millis().into();
+        Ok(Some(serde_json::from_value(data)?))
+    }
+
+    /// Summarizes the execution error of the prompt, if it failed. An error
+    /// status without a readable error message gives an empty summary.
+    pub fn failure(&self) -> Option<Failure> {
+        match self.execution_error() {
+            Ok(Some(error)) => Some(error.into()),
+            Ok(None) => (self.status.status_str == "error").then(Failure::default),
+            Err(_) => Some(Failure::default()),
+        }
+    }
+}
+
+/// Timing of the execution of a prompt, derived from the timestamps of
+/// history messages.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionTiming {
+    /// Submission time, only known when the server records it
+    /// as "create_time" in the extra data of the prompt
+    pub queued: Option<DateTime<Utc>>,
+    pub started: Option<DateTime<Utc>>,
+    /// Time of success, failure or interruption
+    pub ended: Option<DateTime<Utc>>,
+    pub cached_nodes: usize,
+    /// Nodes of the prompt which were not served from cache
+    pub executed_nodes: usize,
+}
+
+impl ExecutionTiming {
+    pub fn wall_time(&self) -> Option<std::time::Duration> {
+        (self.ended? - self.started?).to_std().ok()
+    }
+
+    pub fn queue_wait(&self) -> Option<std::time::Duration> {
+        (self.started? - self.queued?).to_std().ok()
+    }
+}
+
+impl HistoryLogEntry {
+    pub fn timing(&self) -> ExecutionTiming {
+        let mut timing = ExecutionTiming {
+            queued: self.prompt.created(),
+            ..Default::default()
+        };
+        for message in &self.status.messages {
+            let timestamp = Some(message.data.timestamp);
+            match message.kind {
+                MessageKind::Start => timing.started = timestamp,
+                MessageKind::Cached => {
+                    timing.cached_nodes = message
+                        .data
+                        .other
+                        .get("nodes")
+                        .and_then(serde_json::Value::as_array)
+                        .map_or(0, Vec::len);
+                }
+                MessageKind::Success | MessageKind::Error | MessageKind::Interruped => {
+                    timing.ended = timestamp;
+                }
+            }
+        }
+        let nodes = self.prompt.nodes.0.len();
+        timing.executed_nodes = nodes.saturating_sub(timing.cached_nodes);
+        timing
+    }
+}
+
 impl From<History> for PromptBatch {
     fn from(history: History) -> Self {
         let mut result = history.into_batch_entries().collect_vec();
-        result.sort_by_key(|l| l.inner.index);
+        result.sort_by(|a, b| a.inner.index.total_cmp(&b.inner.index));
         result
     }
 }
@@ -0,0 +1,39 @@
+use chrono::{Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// Recent output of the server console, also sent as websocket
+/// messages of type "logs" to subscribed clients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Logs {
+    pub entries: Vec<LogEntry>,
+    pub size: Option<TerminalSize>,
+}
+
+/// Text written at once to the server console, which may hold several
+/// lines or only part of one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// Local time of the server, e.g. '2024-11-04T10:12:13.123456'
+    #[serde(rename = "t")]
+    pub timestamp: String,
+    #[serde(rename = "m")]
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalSize {
+    pub cols: Option<u32>,
+    pub rows: Option<u32>,
+}
+
+impl LogEntry {
+    /// Parses the timestamp, assuming the server shares the local timezone.
+    pub fn time(&self) -> Option<SystemTime> {
+        let time = NaiveDateTime::parse_from_str(&self.timestamp, "%Y-%m-%dT%H:%M:%S%.f").ok()?;
+        Local
+            .from_local_datetime(&time)
+            .earliest()
+            .map(SystemTime::from)
+    }
+}
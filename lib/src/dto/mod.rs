@@ -1,11 +1,23 @@
 mod history;
+mod logs;
+mod object_info;
 mod prompt;
 mod queue;
+mod settings;
 mod stats;
+mod upload;
+mod userdata;
+mod view;
 
 pub mod websocket;
 
 pub use history::*;
+pub use logs::*;
+pub use object_info::*;
 pub use prompt::*;
 pub use queue::*;
+pub use settings::*;
 pub use stats::*;
+pub use upload::*;
+pub use userdata::*;
+pub use view::*;
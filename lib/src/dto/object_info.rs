@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ObjectInfo(pub BTreeMap<String, NodeInfo>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeInfo {
+    pub input: NodeInputs,
+    #[serde(default)]
+    pub input_order: BTreeMap<String, Vec<String>>,
+    pub output: Vec<ValueType>,
+    #[serde(default)]
+    pub output_is_list: Vec<bool>,
+    #[serde(default)]
+    pub output_name: Vec<String>,
+    pub name: String,
+    #[serde(default)]
+    pub display_name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub python_module: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub output_node: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeInputs {
+    #[serde(default)]
+    pub required: BTreeMap<String, InputSpec>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub optional: BTreeMap<String, InputSpec>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hidden: BTreeMap<String, InputSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ValueType {
+    Named(String),
+    Combo(Vec<Value>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "Value", into = "Value")]
+pub struct InputSpec {
+    pub value_type: ValueType,
+    pub options: InputOptions,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tooltip: Option<String>,
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+pub const COMBO: &str = "COMBO";
+
+impl ObjectInfo {
+    pub fn get(&self, class_type: impl AsRef<str>) -> Option<&NodeInfo> {
+        self.0.get(class_type.as_ref())
+    }
+}
+
+impl NodeInfo {
+    pub fn input(&self, name: impl AsRef<str>) -> Option<&InputSpec> {
+        let name = name.as_ref();
+        self.input
+            .required
+            .get(name)
+            .or_else(|| self.input.optional.get(name))
+            .or_else(|| self.input.hidden.get(name))
+    }
+
+    pub fn is_required(&self, name: impl AsRef<str>) -> bool {
+        self.input.required.contains_key(name.as_ref())
+    }
+}
+
+impl ValueType {
+    pub fn name(&self) -> &str {
+        match self {
+            ValueType::Named(name) => name.as_str(),
+            ValueType::Combo(_) => COMBO,
+        }
+    }
+}
+
+impl Display for ValueType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl InputSpec {
+    pub fn combo_options(&self) -> Option<&[Value]> {
+        match &self.value_type {
+            ValueType::Combo(options) => Some(options.as_slice()),
+            ValueType::Named(name) if name == COMBO => self.options.options.as_deref(),
+            ValueType::Named(_) => None,
+        }
+    }
+
+    pub fn is_combo(&self) -> bool {
+        self.combo_options().is_some()
+    }
+}
+
+impl TryFrom<Value> for InputSpec {
+    type Error = serde_json::Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Array(items) => {
+                let mut items = items.into_iter();
+                let value_type = serde_json::from_value(items.next().unwrap_or_default())?;
+                let options = match items.next() {
+                    Some(Value::Object(options)) => serde_json::from_value(Value::Object(options))?,
+                    _ => InputOptions::default(),
+                };
+                Ok(Self {
+                    value_type,
+                    options,
+                })
+            }
+            other => {
+                let value_type = serde_json::from_value(other)?;
+                let options = InputOptions::default();
+                Ok(Self {
+                    value_type,
+                    options,
+                })
+            }
+        }
+    }
+}
+
+impl From<InputSpec> for Value {
+    fn from(spec: InputSpec) -> Self {
+        let value_type = serde_json::to_value(spec.value_type).unwrap_or_default();
+        let options = serde_json::to_value(spec.options).unwrap_or_default();
+        match options {
+            Value::Object(map) if map.is_empty() => Value::Array(vec![value_type]),
+            options => Value::Array(vec![value_type, options]),
+        }
+    }
+}
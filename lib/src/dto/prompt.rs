@@ -1,16 +1,31 @@
-use crate::Result;
+use crate::{Error, Result};
+use chrono::{DateTime, Utc};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Prompt {
-    pub index: i64,
+    /// Number ordering the queue, a float on the server that is only
+    /// fractional for prompts submitted with an explicit number
+    #[serde(serialize_with = "serialize_number")]
+    pub index: f64,
     pub uuid: String,
     pub nodes: PromptNodes,
     pub png_info: serde_json::Value,
     pub output_nodes: Vec<String>,
 }
 
+impl Prompt {
+    /// Submission time, only known when the server records it
+    /// as "create_time" in the extra data of the prompt
+    pub fn created(&self) -> Option<DateTime<Utc>> {
+        self.png_info
+            .get("create_time")
+            .and_then(serde_json::Value::as_i64)
+            .and_then(DateTime::from_timestamp_millis)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct PromptNodes(pub BTreeMap<String, Node<serde_json::Value>>);
@@ -23,9 +38,71 @@ pub struct Node<I> {
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct SubmitResponse {
-    pub number: i32,
+    pub number: f64,
     pub prompt_id: String,
-    pub node_errors: serde_json::Value,
+    #[serde(default)]
+    pub node_errors: BTreeMap<String, NodeValidationErrors>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SubmitOptions {
+    pub front: bool,
+    pub number: Option<f64>,
+    pub prompt_id: Option<String>,
+    pub extra_data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone)]
+pub enum QueuePosition {
+    Front,
+    Back,
+    Before(String),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PromptValidationReport {
+    pub error: ValidationError,
+    #[serde(default)]
+    pub node_errors: BTreeMap<String, NodeValidationErrors>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NodeValidationErrors {
+    pub class_type: String,
+    pub errors: Vec<ValidationError>,
+    #[serde(default)]
+    pub dependent_outputs: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ValidationError {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub message: String,
+    #[serde(default)]
+    pub details: String,
+    #[serde(default)]
+    pub extra_info: ValidationErrorInfo,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ValidationErrorInfo {
+    pub input_name: Option<String>,
+    pub received_value: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+/// Serializes queue numbers as integers when they are integral.
+pub fn serialize_number<S: serde::Serializer>(
+    number: &f64,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    if number.fract() == 0.0 && number.abs() < i64::MAX as f64 {
+        serializer.serialize_i64(*number as i64)
+    } else {
+        serializer.serialize_f64(*number)
+    }
 }
 
 pub trait ClassType {
@@ -44,7 +121,7 @@ impl PromptNodes {
         let node = self
             .0
             .remove(&id)
-            .ok_or(format!("node id '{id}' not found"))?;
+            .ok_or_else(|| Error::NodeNotFound(id.clone()))?;
         Ok(serde_json::from_value(node.inputs)?)
     }
 
@@ -68,11 +145,10 @@ impl PromptNodes {
     where
         N: DeserializeOwned + ClassType,
     {
-        Ok(self
-            .all_by_class::<N>()?
+        self.all_by_class::<N>()?
             .into_iter()
             .next()
-            .ok_or(format!("node with class '{}' not found", N::CLASS_TYPE))?)
+            .ok_or_else(|| Error::ClassNotFound(N::CLASS_TYPE.to_string()))
     }
 
     pub fn change_first_by_class<N, C>(&mut self, change: C) -> Result<()>
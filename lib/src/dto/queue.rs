@@ -32,7 +32,7 @@ impl Queue {
 impl From<Queue> for PromptBatch {
     fn from(queue: Queue) -> Self {
         let mut result = queue.into_batch_entries().collect_vec();
-        result.sort_by_key(|l| l.inner.index);
+        result.sort_by(|a, b| a.inner.index.total_cmp(&b.inner.index));
         result
     }
 }
@@ -0,0 +1,38 @@
+use super::Image;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default)]
+pub struct UploadOptions {
+    pub subfolder: Option<String>,
+    pub _type: Option<String>,
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadResponse {
+    pub name: String,
+    #[serde(default)]
+    pub subfolder: String,
+    #[serde(rename = "type")]
+    pub _type: String,
+}
+
+impl UploadResponse {
+    pub fn path(&self) -> String {
+        if self.subfolder.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}/{}", self.subfolder, self.name)
+        }
+    }
+}
+
+impl From<UploadResponse> for Image {
+    fn from(response: UploadResponse) -> Self {
+        Image {
+            filename: response.name,
+            subfolder: response.subfolder,
+            _type: response._type,
+        }
+    }
+}
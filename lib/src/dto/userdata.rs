@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// A file stored in the user directory of the server, e.g. a saved workflow.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserdataFile {
+    /// Path relative to the listed directory, or to the user
+    /// directory when returned after a write
+    pub path: String,
+    pub size: u64,
+    /// Modification time, in seconds since epoch
+    pub modified: f64,
+}
@@ -0,0 +1,103 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct ViewOptions {
+    pub preview: Option<Preview>,
+    pub channel: Option<Channel>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preview {
+    pub format: PreviewFormat,
+    pub quality: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewFormat {
+    Webp,
+    Jpeg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Rgba,
+    Rgb,
+    Alpha,
+}
+
+const DEFAULT_PREVIEW_QUALITY: u8 = 90;
+
+impl ViewOptions {
+    pub fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = vec![];
+        if let Some(preview) = &self.preview {
+            pairs.push(("preview", preview.to_string()));
+        }
+        if let Some(channel) = &self.channel {
+            pairs.push(("channel", channel.to_string()));
+        }
+        pairs
+    }
+}
+
+impl PreviewFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PreviewFormat::Webp => "webp",
+            PreviewFormat::Jpeg => "jpeg",
+        }
+    }
+}
+
+impl Display for Preview {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{};{}", self.format.extension(), self.quality)
+    }
+}
+
+impl FromStr for Preview {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (format, quality) = s.split_once(';').unwrap_or((s, ""));
+        let format = match format.to_lowercase().as_str() {
+            "webp" => PreviewFormat::Webp,
+            "jpeg" | "jpg" => PreviewFormat::Jpeg,
+            other => return Err(format!("unsupported preview format '{other}'")),
+        };
+        let quality = if quality.is_empty() {
+            DEFAULT_PREVIEW_QUALITY
+        } else {
+            quality
+                .parse()
+                .map_err(|_| format!("invalid preview quality '{quality}'"))?
+        };
+        Ok(Self { format, quality })
+    }
+}
+
+impl Display for Channel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Channel::Rgba => write!(f, "rgba"),
+            Channel::Rgb => write!(f, "rgb"),
+            Channel::Alpha => write!(f, "a"),
+        }
+    }
+}
+
+impl FromStr for Channel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rgba" => Ok(Channel::Rgba),
+            "rgb" => Ok(Channel::Rgb),
+            "a" | "alpha" => Ok(Channel::Alpha),
+            other => Err(format!("unsupported channel '{other}'")),
+        }
+    }
+}
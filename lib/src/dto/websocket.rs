@@ -1,7 +1,9 @@
+use bytes::Bytes;
 use chrono::{serde::ts_milliseconds, DateTime, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-use super::Image;
+use super::{Image, Logs};
 
 #[derive(Deserialize, Clone, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -14,6 +16,15 @@ pub enum Message {
     ExecutionSuccess(Contents<ExecutionStepData>),
     ExecutionCached(Contents<ExecutionStepData>),
     ExecutionInterrupted(Contents<ExecutionStepData>),
+    ExecutionError(Contents<ExecutionError>),
+    ProgressState(Contents<ProgressState>),
+    Logs(Contents<Logs>),
+    #[serde(skip)]
+    Reconnected(Contents<Reconnected>),
+    #[serde(skip)]
+    Binary(BinaryMessage),
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -24,6 +35,7 @@ pub struct Contents<D> {
 #[derive(Deserialize, Clone, Debug)]
 pub struct Status {
     pub status: StatusInner,
+    pub sid: Option<String>,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -61,7 +73,10 @@ pub struct Executed {
 
 #[derive(Deserialize, Clone, Debug)]
 pub struct Outputs {
+    #[serde(default)]
     pub images: Vec<Image>,
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -72,3 +87,315 @@ pub struct ExecutionStepData {
     #[serde(default)]
     pub nodes: Vec<String>,
 }
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ExecutionError {
+    pub prompt_id: String,
+    pub node_id: String,
+    pub node_type: String,
+    #[serde(default)]
+    pub executed: Vec<String>,
+    pub exception_message: String,
+    pub exception_type: String,
+    #[serde(default)]
+    pub traceback: Vec<String>,
+    #[serde(default)]
+    pub current_inputs: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub current_outputs: BTreeMap<String, serde_json::Value>,
+    #[serde(with = "ts_milliseconds")]
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ProgressState {
+    pub prompt_id: String,
+    pub nodes: BTreeMap<String, NodeProgress>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct NodeProgress {
+    pub value: f64,
+    pub max: f64,
+    pub state: NodeState,
+    pub node_id: String,
+    pub display_node_id: Option<String>,
+    pub parent_node_id: Option<String>,
+    pub real_node_id: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeState {
+    Pending,
+    Running,
+    Finished,
+    Error,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Reconnected {
+    pub attempts: u32,
+}
+
+impl Message {
+    pub fn kind(&self) -> &str {
+        use Message::*;
+        match self {
+            Status(_) => "status",
+            Progress(_) => "progress",
+            Executing(_) => "executing",
+            Executed(_) => "executed",
+            ExecutionStart(_) => "execution_start",
+            ExecutionSuccess(_) => "execution_success",
+            ExecutionCached(_) => "execution_cached",
+            ExecutionInterrupted(_) => "execution_interrupted",
+            ExecutionError(_) => "execution_error",
+            ProgressState(_) => "progress_state",
+            Reconnected(_) => "reconnected",
+            Logs(_) => "logs",
+            Binary(BinaryMessage::PreviewImage(_)) => "preview_image",
+            Binary(BinaryMessage::Text { .. }) => "text",
+            Binary(BinaryMessage::Unknown { .. }) => "binary",
+            Unknown(value) => value["type"].as_str().unwrap_or_default(),
+        }
+    }
+
+    pub fn prompt_id(&self) -> Option<&str> {
+        use Message::*;
+        match self {
+            Status(_) | Reconnected(_) | Logs(_) => None,
+            Progress(contents) => Some(&contents.data.prompt_id),
+            Executing(contents) => contents.data.prompt_id.as_deref(),
+            Executed(contents) => Some(&contents.data.prompt_id),
+            ExecutionStart(contents)
+            | ExecutionSuccess(contents)
+            | ExecutionCached(contents)
+            | ExecutionInterrupted(contents) => Some(&contents.data.prompt_id),
+            ExecutionError(contents) => Some(&contents.data.prompt_id),
+            ProgressState(contents) => Some(&contents.data.prompt_id),
+            Binary(BinaryMessage::PreviewImage(preview)) => preview
+                .metadata
+                .as_ref()
+                .map(|metadata| metadata.prompt_id.as_str()),
+            Binary(_) => None,
+            Unknown(value) => value.pointer("/data/prompt_id")?.as_str(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum BinaryMessage {
+    PreviewImage(PreviewImage),
+    Text { node_id: String, text: String },
+    Unknown { event_type: u32, data: Bytes },
+}
+
+#[derive(Clone, Debug)]
+pub struct PreviewImage {
+    pub format: ImageFormat,
+    pub metadata: Option<PreviewMetadata>,
+    pub data: Bytes,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Webp,
+    Unknown,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PreviewMetadata {
+    pub node_id: String,
+    pub prompt_id: String,
+    pub display_node_id: Option<String>,
+    pub parent_node_id: Option<String>,
+    pub real_node_id: Option<String>,
+    pub image_type: Option<String>,
+}
+
+const PREVIEW_IMAGE: u32 = 1;
+const UNENCODED_PREVIEW_IMAGE: u32 = 2;
+const TEXT: u32 = 3;
+const PREVIEW_IMAGE_WITH_METADATA: u32 = 4;
+
+impl BinaryMessage {
+    /// Decodes a binary frame, which starts with the event type as a big
+    /// endian u32. Frames that cannot be decoded are kept as unknown.
+    pub fn decode(data: Bytes) -> Self {
+        let event_type = read_u32(&data, 0);
+        let decoded = match event_type {
+            Some(PREVIEW_IMAGE | UNENCODED_PREVIEW_IMAGE) => read_u32(&data, 4).map(|format| {
+                BinaryMessage::PreviewImage(PreviewImage {
+                    format: ImageFormat::from_id(format),
+                    metadata: None,
+                    data: data.slice(8..),
+                })
+            }),
+            Some(PREVIEW_IMAGE_WITH_METADATA) => read_u32(&data, 4).and_then(|length| {
+                let end = 8usize.checked_add(length as usize)?;
+                let metadata: PreviewMetadata = serde_json::from_slice(data.get(8..end)?).ok()?;
+                let format = metadata
+                    .image_type
+                    .as_deref()
+                    .map(ImageFormat::from_mime_type)
+                    .unwrap_or(ImageFormat::Unknown);
+                Some(BinaryMessage::PreviewImage(PreviewImage {
+                    format,
+                    metadata: Some(metadata),
+                    data: data.slice(end..),
+                }))
+            }),
+            Some(TEXT) => read_u32(&data, 4).and_then(|length| {
+                let end = 8usize.checked_add(length as usize)?;
+                let node_id = std::str::from_utf8(data.get(8..end)?).ok()?;
+                let text = std::str::from_utf8(data.get(end..)?).ok()?;
+                Some(BinaryMessage::Text {
+                    node_id: node_id.to_string(),
+                    text: text.to_string(),
+                })
+            }),
+            _ => None,
+        };
+        decoded.unwrap_or_else(|| BinaryMessage::Unknown {
+            event_type: event_type.unwrap_or_default(),
+            data,
+        })
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes(bytes.try_into().ok()?))
+}
+
+impl ImageFormat {
+    fn from_id(id: u32) -> Self {
+        match id {
+            1 => ImageFormat::Jpeg,
+            2 => ImageFormat::Png,
+            _ => ImageFormat::Unknown,
+        }
+    }
+
+    fn from_mime_type(mime_type: &str) -> Self {
+        match mime_type {
+            "image/jpeg" => ImageFormat::Jpeg,
+            "image/png" => ImageFormat::Png,
+            "image/webp" => ImageFormat::Webp,
+            _ => ImageFormat::Unknown,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Png => "png",
+            ImageFormat::Webp => "webp",
+            ImageFormat::Unknown => "bin",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(event_type: u32, header: u32, payload: &[u8]) -> Bytes {
+        let mut frame = event_type.to_be_bytes().to_vec();
+        frame.extend(header.to_be_bytes());
+        frame.extend(payload);
+        Bytes::from(frame)
+    }
+
+    #[test]
+    fn preview_image() {
+        let decoded = BinaryMessage::decode(frame(PREVIEW_IMAGE, 2, b"png"));
+        let BinaryMessage::PreviewImage(preview) = decoded else {
+            panic!("expected a preview image, got {decoded:?}");
+        };
+        assert_eq!(preview.format, ImageFormat::Png);
+        assert!(preview.metadata.is_none());
+        assert_eq!(&preview.data[..], b"png");
+    }
+
+    #[test]
+    fn preview_image_with_metadata() {
+        let metadata = br#"{"node_id":"3","prompt_id":"abc","image_type":"image/webp"}"#;
+        let mut payload = metadata.to_vec();
+        payload.extend(b"webp");
+        let decoded = BinaryMessage::decode(frame(
+            PREVIEW_IMAGE_WITH_METADATA,
+            metadata.len() as u32,
+            &payload,
+        ));
+        let BinaryMessage::PreviewImage(preview) = decoded else {
+            panic!("expected a preview image, got {decoded:?}");
+        };
+        assert_eq!(preview.format, ImageFormat::Webp);
+        let metadata = preview.metadata.unwrap();
+        assert_eq!(metadata.node_id, "3");
+        assert_eq!(metadata.prompt_id, "abc");
+        assert_eq!(&preview.data[..], b"webp");
+    }
+
+    #[test]
+    fn text() {
+        let decoded = BinaryMessage::decode(frame(TEXT, 2, b"12hello"));
+        let BinaryMessage::Text { node_id, text } = decoded else {
+            panic!("expected text, got {decoded:?}");
+        };
+        assert_eq!(node_id, "12");
+        assert_eq!(text, "hello");
+    }
+
+    #[test]
+    fn truncated_frames_are_unknown() {
+        for data in [&b""[..], &b"\0\0"[..], &b"\0\0\0\x01\0\0"[..]] {
+            let decoded = BinaryMessage::decode(Bytes::copy_from_slice(data));
+            assert!(matches!(decoded, BinaryMessage::Unknown { .. }));
+        }
+        let decoded = BinaryMessage::decode(frame(TEXT, 10, b"12"));
+        assert!(matches!(
+            decoded,
+            BinaryMessage::Unknown {
+                event_type: TEXT,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn metadata_length_overflow_is_unknown() {
+        let decoded = BinaryMessage::decode(frame(PREVIEW_IMAGE_WITH_METADATA, u32::MAX, b"{}"));
+        assert!(matches!(
+            decoded,
+            BinaryMessage::Unknown {
+                event_type: PREVIEW_IMAGE_WITH_METADATA,
+                ..
+            }
+        ));
+        let decoded = BinaryMessage::decode(frame(TEXT, u32::MAX, b"12"));
+        assert!(matches!(
+            decoded,
+            BinaryMessage::Unknown {
+                event_type: TEXT,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn unknown_event_type() {
+        let decoded = BinaryMessage::decode(frame(42, 0, b"data"));
+        let BinaryMessage::Unknown { event_type, data } = decoded else {
+            panic!("expected unknown, got {decoded:?}");
+        };
+        assert_eq!(event_type, 42);
+        assert_eq!(data.len(), 12);
+    }
+}
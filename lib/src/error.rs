@@ -1,2 +1,181 @@
-pub type Error = Box<dyn std::error::Error + Send + Sync>;
+use crate::dto::{websocket::ExecutionError, PromptValidationReport};
+use reqwest::{StatusCode, Url};
+use std::time::Duration;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("could not connect to '{url}': {source}")]
+    Connection { url: String, source: reqwest::Error },
+
+    #[error("'{route}' responded with status {status}: {body}")]
+    HttpStatus {
+        route: String,
+        status: StatusCode,
+        body: String,
+    },
+
+    #[error(transparent)]
+    Http(reqwest::Error),
+
+    #[error("could not decode response from '{route}': {source} (payload: {snippet})")]
+    Decode {
+        route: String,
+        source: serde_json::Error,
+        snippet: String,
+    },
+
+    #[error("unexpected response from '{route}': {body}")]
+    UnexpectedResponse { route: String, body: String },
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("websocket error: {0}")]
+    WebSocket(#[from] tokio_websockets::Error),
+
+    #[error("invalid url: {0}")]
+    Url(#[from] url::ParseError),
+
+    #[error("unsupported url scheme '{0}'")]
+    UnsupportedScheme(String),
+
+    #[error("invalid header: {0}")]
+    InvalidHeader(String),
+
+    #[error("invalid uri: {0}")]
+    Uri(#[from] http::uri::InvalidUri),
+
+    #[error("prompt failed validation: {}", .0.error.message)]
+    PromptValidation(Box<PromptValidationReport>),
+
+    #[error("prompt '{0}' not found")]
+    PromptNotFound(String),
+
+    #[error(
+        "prompt '{}' failed on node '{}' ({}): {}",
+        .0.prompt_id, .0.node_id, .0.node_type, .0.exception_message
+    )]
+    ExecutionFailed(Box<ExecutionError>),
+
+    #[error("prompt '{0}' was interrupted")]
+    Interrupted(String),
+
+    #[error("timed out after {timeout:?} waiting for prompt '{prompt_id}'")]
+    WaitTimeout {
+        prompt_id: String,
+        timeout: Duration,
+    },
+
+    #[error("could not restore prompt(s) to the queue: {}", format_failures(.0))]
+    NotRestored(Vec<(String, Error)>),
+
+    #[error("userdata file '{0}' already exists")]
+    UserdataExists(String),
+
+    #[error("node id '{0}' not found")]
+    NodeNotFound(String),
+
+    #[error("node with class '{0}' not found")]
+    ClassNotFound(String),
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
+
+const SNIPPET_MAX_LEN: usize = 256;
+
+fn format_failures(failures: &[(String, Error)]) -> String {
+    failures
+        .iter()
+        .map(|(prompt_id, error)| format!("'{prompt_id}' ({error})"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Error {
+    pub(crate) fn from_send(error: reqwest::Error) -> Self {
+        if error.is_connect() {
+            let url = error.url().map(Url::to_string).unwrap_or_default();
+            Self::Connection { url, source: error }
+        } else {
+            Self::Http(error)
+        }
+    }
+
+    pub(crate) fn decode(
+        route: impl AsRef<str>,
+        payload: &[u8],
+        source: serde_json::Error,
+    ) -> Self {
+        let route = route.as_ref().to_string();
+        let snippet = snippet(payload);
+        Self::Decode {
+            route,
+            source,
+            snippet,
+        }
+    }
+
+    pub(crate) fn unexpected(route: impl AsRef<str>, payload: &[u8]) -> Self {
+        let route = route.as_ref().to_string();
+        let body = snippet(payload);
+        Self::UnexpectedResponse { route, body }
+    }
+}
+
+fn snippet(payload: &[u8]) -> String {
+    let text = String::from_utf8_lossy(payload);
+    if text.chars().count() > SNIPPET_MAX_LEN {
+        let truncated: String = text.chars().take(SNIPPET_MAX_LEN).collect();
+        format!("{truncated}...")
+    } else {
+        text.into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json_error() -> serde_json::Error {
+        serde_json::from_str::<serde_json::Value>("{").unwrap_err()
+    }
+
+    fn decoded_snippet(payload: &[u8]) -> String {
+        match Error::decode("route", payload, json_error()) {
+            Error::Decode { route, snippet, .. } => {
+                assert_eq!(route, "route");
+                snippet
+            }
+            error => panic!("expected a decode error, got {error:?}"),
+        }
+    }
+
+    #[test]
+    fn short_payload_is_kept() {
+        assert_eq!(decoded_snippet(b"<html>"), "<html>");
+    }
+
+    #[test]
+    fn long_payload_is_truncated() {
+        let payload = "x".repeat(SNIPPET_MAX_LEN + 10);
+        let snippet = decoded_snippet(payload.as_bytes());
+        assert_eq!(snippet, format!("{}...", "x".repeat(SNIPPET_MAX_LEN)));
+    }
+
+    #[test]
+    fn truncation_counts_characters() {
+        let payload = "é".repeat(SNIPPET_MAX_LEN);
+        assert_eq!(decoded_snippet(payload.as_bytes()), payload);
+        let payload = "é".repeat(SNIPPET_MAX_LEN + 1);
+        let snippet = decoded_snippet(payload.as_bytes());
+        assert_eq!(snippet.chars().count(), SNIPPET_MAX_LEN + 3);
+    }
+
+    #[test]
+    fn invalid_utf8_is_replaced() {
+        assert_eq!(decoded_snippet(b"ok\xff"), "ok\u{fffd}");
+    }
+}
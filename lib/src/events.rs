@@ -0,0 +1,125 @@
+use crate::{dto::websocket::Message, Client, MessageStream, Result};
+use futures_util::{future, stream, Stream, StreamExt};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+pub struct Events {
+    inner: Pin<Box<dyn Stream<Item = Result<Message>> + Send>>,
+}
+
+impl Events {
+    fn from_stream(stream: impl Stream<Item = Result<Message>> + Send + 'static) -> Self {
+        Self {
+            inner: Box::pin(stream),
+        }
+    }
+
+    pub fn for_prompt(self, prompt_id: impl Into<String>) -> Self {
+        let prompt_id = prompt_id.into();
+        Self::from_stream(self.filter(move |event| {
+            future::ready(match event {
+                Ok(message) => message.prompt_id() == Some(prompt_id.as_str()),
+                Err(_) => true,
+            })
+        }))
+    }
+
+    pub fn of_kind<S: Into<String>>(self, kinds: impl IntoIterator<Item = S>) -> Self {
+        let kinds = kinds.into_iter().map(Into::into).collect::<Vec<String>>();
+        Self::from_stream(self.filter(move |event| {
+            future::ready(match event {
+                Ok(message) => kinds.iter().any(|kind| kind == message.kind()),
+                Err(_) => true,
+            })
+        }))
+    }
+
+    /// Only keeps messages about prompts submitted with the id of the given
+    /// client, which the server otherwise broadcasts when submitted without
+    /// id. Messages unrelated to a prompt are kept. The submitter of each
+    /// prompt is looked up once from the queue or history, messages being
+    /// kept while the lookup fails.
+    pub fn from_client(self, client: &Client) -> Self {
+        let client = client.clone();
+        let submitters = Arc::new(Mutex::new(HashMap::<String, bool>::new()));
+        Self::from_stream(self.filter(move |event| {
+            let client = client.clone();
+            let submitters = submitters.clone();
+            let prompt_id = match event {
+                Ok(message) => message.prompt_id().map(String::from),
+                Err(_) => None,
+            };
+            async move {
+                let Some(prompt_id) = prompt_id else {
+                    return true;
+                };
+                if let Some(own) = submitters.lock().unwrap().get(&prompt_id) {
+                    return *own;
+                }
+                match client.is_submitter_of(&prompt_id).await {
+                    Ok(own) => {
+                        submitters.lock().unwrap().insert(prompt_id, own);
+                        own
+                    }
+                    // looked up again on the next message, which is safer
+                    // than dropping all further messages about the prompt
+                    Err(_) => true,
+                }
+            }
+        }))
+    }
+}
+
+impl From<MessageStream> for Events {
+    fn from(stream: MessageStream) -> Self {
+        // the stream ends after the first error
+        Self::from_stream(stream::unfold(Some(stream), |stream| async move {
+            let mut stream = stream?;
+            match stream.next_message().await {
+                Ok(Some(message)) => Some((Ok(message), Some(stream))),
+                Ok(None) => None,
+                Err(error) => Some((Err(error), None)),
+            }
+        }))
+    }
+}
+
+impl Stream for Events {
+    type Item = Result<Message>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl MessageStream {
+    pub fn into_events(self) -> Events {
+        self.into()
+    }
+}
+
+impl Client {
+    pub async fn events(&self) -> Result<Events> {
+        Ok(self.listen().await?.into_events())
+    }
+
+    async fn is_submitter_of(&self, prompt_id: &str) -> Result<bool> {
+        let queue = self.queue().await?;
+        let prompt = queue
+            .running
+            .into_iter()
+            .chain(queue.pending)
+            .find(|prompt| prompt.uuid == prompt_id);
+        let prompt = match prompt {
+            Some(prompt) => Some(prompt),
+            None => self.history_for(prompt_id).await?.map(|entry| entry.prompt),
+        };
+        Ok(prompt.is_some_and(|prompt| {
+            prompt.png_info.get("client_id").and_then(|id| id.as_str()) == Some(&self.id)
+        }))
+    }
+}
@@ -1,12 +1,24 @@
+mod builder;
 mod client;
+mod download;
 mod error;
+mod events;
+mod retry;
 mod status;
+mod wait;
 mod websocket;
 
 pub mod dto;
+pub mod validation;
 
-pub use client::Client;
+pub use builder::ClientBuilder;
+pub use client::{Auth, Client};
+pub use download::ImageDownload;
 pub use dto::{History, Prompt, Queue, SystemStats};
 pub use error::{Error, Result};
-pub use status::{MarkAs, Status, WithStatus};
-pub use websocket::MessageStream;
+pub use events::Events;
+pub use retry::RetryPolicy;
+pub use status::{Failure, MarkAs, Status, WithStatus};
+pub use validation::validate;
+pub use wait::WaitOptions;
+pub use websocket::{Frame, MessageStream, ReconnectOptions};
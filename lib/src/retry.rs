@@ -0,0 +1,172 @@
+use crate::{Error, Result};
+use std::{future::Future, time::Duration};
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.powf(attempt as f64);
+        // computed in seconds, the factor overflows for large attempt counts
+        let seconds =
+            (self.initial_backoff.as_secs_f64() * factor).min(self.max_backoff.as_secs_f64());
+        let backoff = Duration::from_secs_f64(seconds);
+        if self.jitter {
+            backoff.mul_f64(rand::random_range(0.5..=1.0))
+        } else {
+            backoff
+        }
+    }
+
+    pub(crate) async fn run<T, F, Fut>(&self, mut operation: F) -> Result<T>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match operation(attempt).await {
+                Err(error) if error.is_transient() && attempt < self.max_retries => {
+                    tokio::time::sleep(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Error {
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::Connection { .. } => true,
+            Error::Http(error) => {
+                error.is_timeout() || error.is_connect() || error.is_request() || error.is_body()
+            }
+            Error::HttpStatus { status, .. } => matches!(status.as_u16(), 429 | 502 | 503 | 504),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+    use std::cell::Cell;
+
+    fn status(code: u16) -> Error {
+        Error::HttpStatus {
+            route: "queue".to_string(),
+            status: StatusCode::from_u16(code).unwrap(),
+            body: String::new(),
+        }
+    }
+
+    fn immediate(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            initial_backoff: Duration::ZERO,
+            jitter: false,
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn backoff_grows_up_to_max() {
+        let policy = RetryPolicy {
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        let delays = (0..8)
+            .map(|attempt| policy.backoff(attempt))
+            .collect::<Vec<_>>();
+        assert_eq!(delays[0], Duration::from_millis(250));
+        assert_eq!(delays[1], Duration::from_millis(500));
+        assert_eq!(delays[3], Duration::from_secs(2));
+        assert_eq!(delays[7], Duration::from_secs(10));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn jitter_stays_within_half_of_backoff() {
+        let policy = RetryPolicy::default();
+        for _ in 0..100 {
+            let delay = policy.backoff(2);
+            assert!(delay >= Duration::from_millis(500) && delay <= Duration::from_secs(1));
+        }
+    }
+
+    #[test]
+    fn transient_errors() {
+        for code in [429, 502, 503, 504] {
+            assert!(status(code).is_transient(), "{code}");
+        }
+        for code in [400, 404, 500] {
+            assert!(!status(code).is_transient(), "{code}");
+        }
+        assert!(!Error::PromptNotFound("id".to_string()).is_transient());
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors_until_max() {
+        let attempts = Cell::new(0);
+        let result: Result<()> = immediate(2)
+            .run(|attempt| {
+                attempts.set(attempts.get() + 1);
+                assert_eq!(attempt + 1, attempts.get());
+                async { Err(status(503)) }
+            })
+            .await;
+        assert!(matches!(result, Err(Error::HttpStatus { .. })));
+        assert_eq!(attempts.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn stops_on_success_or_permanent_error() {
+        let result = immediate(3)
+            .run(|attempt| async move {
+                match attempt {
+                    0 => Err(status(502)),
+                    _ => Ok(attempt),
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 1);
+
+        let attempts = Cell::new(0);
+        let result: Result<()> = immediate(3)
+            .run(|_| {
+                attempts.set(attempts.get() + 1);
+                async { Err(status(500)) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), 1);
+    }
+}
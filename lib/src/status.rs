@@ -2,6 +2,8 @@ use std::fmt::{Display, Formatter};
 
 use colored::{ColoredString, Colorize};
 
+use crate::dto::websocket::ExecutionError;
+
 #[derive(Debug, Clone)]
 pub struct WithStatus<I, O> {
     pub inner: I,
@@ -14,6 +16,27 @@ pub enum Status<O> {
     Pending,
     Running,
     Cancelled,
+    Failed(Failure),
+}
+
+/// Summary of the error that stopped the execution of a prompt.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Failure {
+    pub node_id: String,
+    pub node_type: String,
+    pub exception_type: String,
+    pub exception_message: String,
+}
+
+impl From<ExecutionError> for Failure {
+    fn from(error: ExecutionError) -> Self {
+        Self {
+            node_id: error.node_id,
+            node_type: error.node_type,
+            exception_type: error.exception_type,
+            exception_message: error.exception_message,
+        }
+    }
 }
 
 pub trait MarkAs {
@@ -34,7 +57,24 @@ impl<O> Display for Status<O> {
             Pending => write!(f, "pending"),
             Running => write!(f, "running"),
             Cancelled => write!(f, "cancelled"),
+            Failed(_) => write!(f, "failed"),
+        }
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.node_id.is_empty() {
+            return write!(f, "execution failed");
         }
+        write!(
+            f,
+            "node '{}' ({}) raised {}: {}",
+            self.node_id,
+            self.node_type,
+            self.exception_type,
+            self.exception_message.trim()
+        )
     }
 }
 
@@ -49,6 +89,7 @@ impl<O> Status<O> {
             Pending => Pending,
             Running => Running,
             Cancelled => Cancelled,
+            Failed(failure) => Failed(failure),
         }
     }
 
@@ -58,6 +99,7 @@ impl<O> Status<O> {
             Status::Pending => self.to_string().yellow(),
             Status::Running => self.to_string().blue(),
             Status::Cancelled => self.to_string().red(),
+            Status::Failed(_) => self.to_string().red(),
         }
     }
 }
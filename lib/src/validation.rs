@@ -0,0 +1,319 @@
+use crate::dto::{InputSpec, NodeInfo, ObjectInfo, PromptNodes, ValueType};
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub node_id: String,
+    pub class_type: String,
+    pub kind: IssueKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum IssueKind {
+    UnknownClassType,
+    MissingRequiredInput {
+        input: String,
+    },
+    OutOfRange {
+        input: String,
+        value: f64,
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    InvalidComboValue {
+        input: String,
+        value: Value,
+    },
+    MissingLinkSource {
+        input: String,
+        source_id: String,
+    },
+    InvalidLinkSlot {
+        input: String,
+        source_id: String,
+        slot: u64,
+    },
+    LinkTypeMismatch {
+        input: String,
+        expected: String,
+        found: String,
+    },
+}
+
+pub fn validate(nodes: &PromptNodes, schema: &ObjectInfo) -> Vec<Issue> {
+    let mut issues = vec![];
+    for (node_id, node) in &nodes.0 {
+        let mut report = |kind| {
+            issues.push(Issue {
+                node_id: node_id.clone(),
+                class_type: node.class_type.clone(),
+                kind,
+            })
+        };
+
+        let Some(info) = schema.get(&node.class_type) else {
+            report(IssueKind::UnknownClassType);
+            continue;
+        };
+
+        for input in info.input.required.keys() {
+            if node.inputs.get(input).is_none() {
+                let input = input.clone();
+                report(IssueKind::MissingRequiredInput { input });
+            }
+        }
+
+        let Some(inputs) = node.inputs.as_object() else {
+            continue;
+        };
+        for (input, value) in inputs {
+            let Some(spec) = info.input(input) else {
+                continue;
+            };
+            let kind = match as_link(value) {
+                Some((source_id, slot)) => check_link(nodes, schema, input, spec, source_id, slot),
+                None => check_value(input, spec, value),
+            };
+            if let Some(kind) = kind {
+                report(kind);
+            }
+        }
+    }
+    issues
+}
+
+fn as_link(value: &Value) -> Option<(&str, u64)> {
+    match value.as_array()?.as_slice() {
+        [Value::String(source_id), Value::Number(slot)] => Some((source_id, slot.as_u64()?)),
+        _ => None,
+    }
+}
+
+fn check_value(input: &str, spec: &InputSpec, value: &Value) -> Option<IssueKind> {
+    if let Some(options) = spec.combo_options() {
+        if !options.contains(value) {
+            let input = input.to_string();
+            let value = value.clone();
+            return Some(IssueKind::InvalidComboValue { input, value });
+        }
+    }
+    let number = value.as_f64()?;
+    let (min, max) = (spec.options.min, spec.options.max);
+    let below = min.is_some_and(|min| number < min);
+    let above = max.is_some_and(|max| number > max);
+    if below || above {
+        let input = input.to_string();
+        return Some(IssueKind::OutOfRange {
+            input,
+            value: number,
+            min,
+            max,
+        });
+    }
+    None
+}
+
+fn check_link(
+    nodes: &PromptNodes,
+    schema: &ObjectInfo,
+    input: &str,
+    spec: &InputSpec,
+    source_id: &str,
+    slot: u64,
+) -> Option<IssueKind> {
+    let input = input.to_string();
+    let Some(source) = nodes.0.get(source_id) else {
+        let source_id = source_id.to_string();
+        return Some(IssueKind::MissingLinkSource { input, source_id });
+    };
+    // unknown source classes are already reported on their own
+    let source_info: &NodeInfo = schema.get(&source.class_type)?;
+    let Some(output) = source_info.output.get(slot as usize) else {
+        let source_id = source_id.to_string();
+        return Some(IssueKind::InvalidLinkSlot {
+            input,
+            source_id,
+            slot,
+        });
+    };
+    if types_match(&spec.value_type, output) {
+        None
+    } else {
+        let expected = spec.value_type.to_string();
+        let found = output.to_string();
+        Some(IssueKind::LinkTypeMismatch {
+            input,
+            expected,
+            found,
+        })
+    }
+}
+
+fn types_match(expected: &ValueType, found: &ValueType) -> bool {
+    let expected = expected.name();
+    let found = found.name();
+    if expected == "*" || found == "*" {
+        return true;
+    }
+    expected.split(',').any(|expected| {
+        found
+            .split(',')
+            .any(|found| expected.trim() == found.trim())
+    })
+}
+
+impl Display for IssueKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use IssueKind::*;
+        match self {
+            UnknownClassType => write!(f, "unknown class type (missing custom node?)"),
+            MissingRequiredInput { input } => write!(f, "{input}: missing required input"),
+            OutOfRange {
+                input,
+                value,
+                min,
+                max,
+            } => {
+                let min = min.map(|min| min.to_string()).unwrap_or("-inf".into());
+                let max = max.map(|max| max.to_string()).unwrap_or("+inf".into());
+                write!(f, "{input}: value {value} is outside of [{min}, {max}]")
+            }
+            InvalidComboValue { input, value } => {
+                write!(
+                    f,
+                    "{input}: value {value} is not one of the available options"
+                )
+            }
+            MissingLinkSource { input, source_id } => {
+                write!(f, "{input}: linked to missing node '{source_id}'")
+            }
+            InvalidLinkSlot {
+                input,
+                source_id,
+                slot,
+            } => write!(f, "{input}: node '{source_id}' has no output {slot}"),
+            LinkTypeMismatch {
+                input,
+                expected,
+                found,
+            } => write!(f, "{input}: expected {expected}, linked to {found}"),
+        }
+    }
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}: {}", self.node_id, self.class_type, self.kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> ObjectInfo {
+        serde_json::from_value(json!({
+            "Loader": {
+                "name": "Loader",
+                "input": {"required": {"ckpt_name": [["a.safetensors", "b.safetensors"]]}},
+                "output": ["MODEL", "CLIP"]
+            },
+            "Sampler": {
+                "name": "Sampler",
+                "input": {
+                    "required": {
+                        "model": ["MODEL"],
+                        "steps": ["INT", {"min": 1, "max": 100}],
+                        "sampler": ["COMBO", {"options": ["euler", "dpmpp_2m"]}]
+                    },
+                    "optional": {"any": ["*"], "union": ["CLIP,VAE"]}
+                },
+                "output": ["LATENT"]
+            }
+        }))
+        .unwrap()
+    }
+
+    fn issues(nodes: Value) -> Vec<IssueKind> {
+        let nodes: PromptNodes = serde_json::from_value(nodes).unwrap();
+        validate(&nodes, &schema())
+            .into_iter()
+            .map(|issue| issue.kind)
+            .collect()
+    }
+
+    fn sampler(inputs: Value) -> Value {
+        let mut inputs_with_defaults = json!({
+            "model": ["1", 0],
+            "steps": 20,
+            "sampler": "euler"
+        });
+        inputs_with_defaults
+            .as_object_mut()
+            .unwrap()
+            .extend(inputs.as_object().unwrap().clone());
+        json!({
+            "1": {"class_type": "Loader", "inputs": {"ckpt_name": "a.safetensors"}},
+            "2": {"class_type": "Sampler", "inputs": inputs_with_defaults}
+        })
+    }
+
+    #[test]
+    fn valid_prompt() {
+        assert!(issues(sampler(json!({}))).is_empty());
+    }
+
+    #[test]
+    fn unknown_class_type() {
+        let kinds = issues(json!({"1": {"class_type": "Custom", "inputs": {}}}));
+        assert!(matches!(kinds[..], [IssueKind::UnknownClassType]));
+    }
+
+    #[test]
+    fn missing_required_input() {
+        let kinds = issues(json!({"1": {"class_type": "Loader", "inputs": {}}}));
+        assert!(
+            matches!(&kinds[..], [IssueKind::MissingRequiredInput { input }] if input == "ckpt_name")
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        let kinds = issues(sampler(json!({"steps": 150})));
+        assert!(matches!(kinds[..], [IssueKind::OutOfRange { value, .. }] if value == 150.0));
+    }
+
+    #[test]
+    fn invalid_combo_value() {
+        let kinds = issues(sampler(json!({"sampler": "ddim"})));
+        assert!(matches!(kinds[..], [IssueKind::InvalidComboValue { .. }]));
+        let kinds =
+            issues(json!({"1": {"class_type": "Loader", "inputs": {"ckpt_name": "c.ckpt"}}}));
+        assert!(matches!(kinds[..], [IssueKind::InvalidComboValue { .. }]));
+    }
+
+    #[test]
+    fn invalid_links() {
+        let kinds = issues(sampler(json!({"model": ["9", 0]})));
+        assert!(matches!(kinds[..], [IssueKind::MissingLinkSource { .. }]));
+        let kinds = issues(sampler(json!({"model": ["1", 2]})));
+        assert!(matches!(
+            kinds[..],
+            [IssueKind::InvalidLinkSlot { slot: 2, .. }]
+        ));
+        let kinds = issues(sampler(json!({"model": ["1", 1]})));
+        assert!(
+            matches!(&kinds[..], [IssueKind::LinkTypeMismatch { expected, found, .. }]
+            if expected == "MODEL" && found == "CLIP")
+        );
+    }
+
+    #[test]
+    fn wildcard_and_union_types() {
+        assert!(issues(sampler(json!({"any": ["1", 0], "union": ["1", 1]}))).is_empty());
+        let kinds = issues(sampler(json!({"union": ["1", 0]})));
+        assert!(matches!(kinds[..], [IssueKind::LinkTypeMismatch { .. }]));
+    }
+}
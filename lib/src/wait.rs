@@ -0,0 +1,153 @@
+use crate::{
+    dto::{self, websocket::Message},
+    Client, Error, MessageStream, Result,
+};
+use serde_json::json;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct WaitOptions {
+    pub timeout: Option<Duration>,
+    pub poll_interval: Duration,
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl Client {
+    pub async fn submit_and_wait(&self, nodes: &dto::PromptNodes) -> Result<dto::Outputs> {
+        self.submit_and_wait_with(nodes, &WaitOptions::default(), |_| {})
+            .await
+    }
+
+    pub async fn submit_and_wait_with(
+        &self,
+        nodes: &dto::PromptNodes,
+        options: &WaitOptions,
+        on_message: impl FnMut(&Message),
+    ) -> Result<dto::Outputs> {
+        // the server only sends execution messages to the connection of the
+        // submitting client, a dedicated id keeps other connections sharing
+        // this client's id from taking them over
+        let client_id = Uuid::new_v4().to_string();
+        let stream = self.listen_as(&client_id).await?;
+        let submit_options = dto::SubmitOptions {
+            extra_data: Some(json!({ "client_id": client_id })),
+            ..Default::default()
+        };
+        let response = self.submit_with(nodes, &submit_options).await?;
+        self.wait_on(Some(stream), &response.prompt_id, options, on_message)
+            .await
+    }
+
+    /// Waits for a prompt submitted by any client by polling the queue and
+    /// history, since execution messages are only sent to the submitter.
+    pub async fn wait_for(
+        &self,
+        prompt_id: impl AsRef<str>,
+        options: &WaitOptions,
+    ) -> Result<dto::Outputs> {
+        self.wait_on(None, prompt_id.as_ref(), options, |_| {})
+            .await
+    }
+
+    async fn wait_on(
+        &self,
+        stream: Option<MessageStream>,
+        prompt_id: &str,
+        options: &WaitOptions,
+        on_message: impl FnMut(&Message),
+    ) -> Result<dto::Outputs> {
+        let wait = self.wait_until_done(stream, prompt_id, options.poll_interval, on_message);
+        match options.timeout {
+            Some(timeout) => {
+                tokio::time::timeout(timeout, wait)
+                    .await
+                    .map_err(|_| Error::WaitTimeout {
+                        prompt_id: prompt_id.to_string(),
+                        timeout,
+                    })?
+            }
+            None => wait.await,
+        }
+    }
+
+    async fn wait_until_done(
+        &self,
+        mut stream: Option<MessageStream>,
+        prompt_id: &str,
+        poll_interval: Duration,
+        mut on_message: impl FnMut(&Message),
+    ) -> Result<dto::Outputs> {
+        loop {
+            let Some(websocket) = &mut stream else {
+                if let Some(outcome) = self.outcome(prompt_id).await? {
+                    return outcome;
+                }
+                tokio::time::sleep(poll_interval).await;
+                continue;
+            };
+            let message: Message = match websocket.next_json_with_timeout(poll_interval).await {
+                Ok(Ok(Some(message))) => message,
+                // polling also covers messages that never reached the stream
+                Err(_timeout) => {
+                    if let Some(outcome) = self.outcome(prompt_id).await? {
+                        return outcome;
+                    }
+                    continue;
+                }
+                Ok(Ok(None)) => {
+                    stream = None;
+                    continue;
+                }
+                Ok(Err(error)) => return Err(error),
+            };
+            if message.prompt_id() != Some(prompt_id) {
+                continue;
+            }
+            on_message(&message);
+            match message {
+                // outputs are only stored in history once execution is over
+                Message::ExecutionSuccess(_) => stream = None,
+                Message::ExecutionInterrupted(_) => {
+                    return Err(Error::Interrupted(prompt_id.to_string()))
+                }
+                Message::ExecutionError(contents) => {
+                    return Err(Error::ExecutionFailed(Box::new(contents.data)))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    async fn outcome(&self, prompt_id: &str) -> Result<Option<Result<dto::Outputs>>> {
+        // the queue is checked first, a prompt leaving it is already in history
+        let queue = self.queue().await?;
+        if queue
+            .running
+            .iter()
+            .chain(queue.pending.iter())
+            .any(|prompt| prompt.uuid == prompt_id)
+        {
+            return Ok(None);
+        }
+        let Some(entry) = self.history_for(prompt_id).await? else {
+            return Err(Error::PromptNotFound(prompt_id.to_string()));
+        };
+        if let Some(error) = entry.execution_error()? {
+            return Ok(Some(Err(Error::ExecutionFailed(Box::new(error)))));
+        }
+        if entry.status.completed {
+            Ok(Some(Ok(entry.outputs)))
+        } else {
+            Ok(Some(Err(Error::Interrupted(prompt_id.to_string()))))
+        }
+    }
+}
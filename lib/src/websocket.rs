@@ -1,39 +1,236 @@
 use std::time::Duration;
 
-use crate::Result;
-use futures_util::StreamExt;
-use http::Uri;
+use crate::{
+    dto::websocket::{BinaryMessage, Contents, Message, Reconnected},
+    Error, Result, RetryPolicy,
+};
+use futures_util::{SinkExt, StreamExt};
+use http::{HeaderMap, Uri};
 use serde::de::DeserializeOwned;
-use tokio::{net::TcpStream, time::error::Elapsed};
+use tokio::{
+    net::TcpStream,
+    time::{error::Elapsed, Instant},
+};
 use tokio_websockets::{MaybeTlsStream, WebSocketStream};
 
+type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
 pub struct MessageStream {
-    websocket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    websocket: Option<WebSocket>,
+    address: Option<String>,
+    headers: HeaderMap,
+    reconnect: Option<Reconnect>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReconnectOptions {
+    pub keepalive: Duration,
+    pub retry_policy: RetryPolicy,
+}
+
+impl Default for ReconnectOptions {
+    fn default() -> Self {
+        Self {
+            keepalive: Duration::from_secs(10),
+            retry_policy: RetryPolicy {
+                max_retries: u32::MAX,
+                ..RetryPolicy::default()
+            },
+        }
+    }
+}
+
+// kept on the stream rather than in `next_json`, which may be cancelled
+// at any await point, e.g. by `next_json_with_timeout`
+struct Reconnect {
+    options: ReconnectOptions,
+    last_seen: Instant,
+    ping_sent: bool,
+    attempt: u32,
+    retry_at: Instant,
+    reconnected: Option<u32>,
+}
+
+pub enum Frame<T> {
+    Json(T),
+    Binary(BinaryMessage),
+    /// Yielded by streams with reconnection once the connection is reopened
+    Reconnected(Reconnected),
 }
 
 pub type MaybeTimeout<T> = std::result::Result<T, Elapsed>;
 
 impl MessageStream {
-    pub fn new(websocket: WebSocketStream<MaybeTlsStream<TcpStream>>) -> Self {
-        Self { websocket }
+    pub fn new(websocket: WebSocket) -> Self {
+        Self {
+            websocket: Some(websocket),
+            address: None,
+            headers: HeaderMap::new(),
+            reconnect: None,
+        }
     }
 
     pub async fn open(address: impl AsRef<str>) -> Result<Self> {
-        let uri = address.as_ref().parse::<Uri>()?;
-        let (stream, _) = tokio_websockets::ClientBuilder::from_uri(uri)
-            .connect()
-            .await?;
-        Ok(MessageStream::new(stream))
+        Self::open_with_headers(address, &HeaderMap::new()).await
+    }
+
+    pub async fn open_with_headers(address: impl AsRef<str>, headers: &HeaderMap) -> Result<Self> {
+        let address = address.as_ref().to_string();
+        let websocket = Self::connect(&address, headers).await?;
+        Ok(Self {
+            websocket: Some(websocket),
+            address: Some(address),
+            headers: headers.clone(),
+            reconnect: None,
+        })
+    }
+
+    async fn connect(address: &str, headers: &HeaderMap) -> Result<WebSocket> {
+        let uri = address.parse::<Uri>()?;
+        let mut builder = tokio_websockets::ClientBuilder::from_uri(uri);
+        for (name, value) in headers {
+            builder = builder.add_header(name.clone(), value.clone())?;
+        }
+        let (stream, _) = builder.connect().await?;
+        Ok(stream)
+    }
+
+    /// Keeps the stream alive across connection losses: the connection is
+    /// checked with pings when idle and reopened with the same address
+    /// when lost. Each reconnection yields a `Frame::Reconnected`, or a
+    /// `Message::Reconnected` from `next_message`, as the server state may
+    /// have changed in between.
+    /// Only applies to streams opened from an address.
+    pub fn with_reconnect(mut self, options: ReconnectOptions) -> Self {
+        if self.address.is_some() {
+            let now = Instant::now();
+            self.reconnect = Some(Reconnect {
+                options,
+                last_seen: now,
+                ping_sent: false,
+                attempt: 0,
+                retry_at: now,
+                reconnected: None,
+            });
+        }
+        self
     }
 
     pub async fn next_json<T: DeserializeOwned>(&mut self) -> Result<Option<T>> {
-        while let Some(message) = self.websocket.next().await.transpose()? {
-            if let Some(text) = message.as_text() {
-                let value = serde_json::from_str(text)?;
-                return Ok(Some(value));
+        loop {
+            match self.next_frame().await? {
+                Some(Frame::Json(value)) => return Ok(Some(value)),
+                Some(Frame::Binary(_) | Frame::Reconnected(_)) => continue,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    pub async fn next_message(&mut self) -> Result<Option<Message>> {
+        Ok(self.next_frame().await?.map(|frame| match frame {
+            Frame::Json(message) => message,
+            Frame::Binary(binary) => Message::Binary(binary),
+            Frame::Reconnected(data) => Message::Reconnected(Contents { data }),
+        }))
+    }
+
+    pub async fn next_frame<T: DeserializeOwned>(&mut self) -> Result<Option<Frame<T>>> {
+        loop {
+            if let Some(attempts) = self
+                .reconnect
+                .as_mut()
+                .and_then(|reconnect| reconnect.reconnected.take())
+            {
+                return Ok(Some(Frame::Reconnected(Reconnected { attempts })));
+            }
+            let Some(websocket) = &mut self.websocket else {
+                self.reopen().await?;
+                continue;
+            };
+            let next = match &mut self.reconnect {
+                None => websocket.next().await,
+                Some(reconnect) => {
+                    let idle = reconnect.options.keepalive * (1 + reconnect.ping_sent as u32);
+                    let deadline = reconnect.last_seen + idle;
+                    match tokio::time::timeout_at(deadline, websocket.next()).await {
+                        Ok(next) => next,
+                        Err(_idle) if !reconnect.ping_sent => {
+                            let ping = tokio_websockets::Message::ping(vec![]);
+                            if websocket.send(ping).await.is_err() {
+                                self.disconnect();
+                            } else {
+                                reconnect.ping_sent = true;
+                            }
+                            continue;
+                        }
+                        Err(_dead) => {
+                            self.disconnect();
+                            continue;
+                        }
+                    }
+                }
+            };
+            match next {
+                Some(Ok(message)) => {
+                    if let Some(reconnect) = &mut self.reconnect {
+                        reconnect.last_seen = Instant::now();
+                        reconnect.ping_sent = false;
+                    }
+                    if let Some(text) = message.as_text() {
+                        return Self::decode(text).map(|value| Some(Frame::Json(value)));
+                    }
+                    if message.is_binary() {
+                        let binary = BinaryMessage::decode(message.into_payload().into());
+                        return Ok(Some(Frame::Binary(binary)));
+                    }
+                }
+                _ if self.reconnect.is_some() => self.disconnect(),
+                Some(Err(error)) => return Err(error.into()),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(text: &str) -> Result<T> {
+        serde_json::from_str(text).map_err(|error| Error::decode("ws", text.as_bytes(), error))
+    }
+
+    fn disconnect(&mut self) {
+        self.websocket = None;
+        if let Some(reconnect) = &mut self.reconnect {
+            reconnect.attempt = 0;
+            reconnect.retry_at = Instant::now() + reconnect.options.retry_policy.backoff(0);
+        }
+    }
+
+    async fn reopen(&mut self) -> Result<()> {
+        let (Some(address), Some(reconnect)) = (&self.address, &mut self.reconnect) else {
+            return Ok(());
+        };
+        tokio::time::sleep_until(reconnect.retry_at).await;
+        // an unresponsive server may accept connections without answering
+        let connect = Self::connect(address, &self.headers);
+        let connected = tokio::time::timeout(reconnect.options.keepalive, connect)
+            .await
+            .unwrap_or_else(|_| Err(std::io::Error::from(std::io::ErrorKind::TimedOut).into()));
+        match connected {
+            Ok(websocket) => {
+                self.websocket = Some(websocket);
+                reconnect.reconnected = Some(reconnect.attempt + 1);
+                reconnect.last_seen = Instant::now();
+                reconnect.ping_sent = false;
+                Ok(())
+            }
+            Err(error) if reconnect.attempt >= reconnect.options.retry_policy.max_retries => {
+                Err(error)
+            }
+            Err(_) => {
+                reconnect.attempt += 1;
+                let backoff = reconnect.options.retry_policy.backoff(reconnect.attempt);
+                reconnect.retry_at = Instant::now() + backoff;
+                Ok(())
             }
         }
-        Ok(None)
     }
 
     pub async fn next_json_with_timeout<T: DeserializeOwned>(
@@ -12,6 +12,12 @@ pub struct LoraLoaderInputs {
     pub other: serde_json::Value,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Node)]
+#[node(class_type = "CheckpointLoaderSimple")]
+pub struct CheckpointLoaderSimpleInputs {
+    pub ckpt_name: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Node)]
 #[node(class_type = "KSampler")]
 pub struct KSamplerInputs {
@@ -1,14 +1,77 @@
+use crate::{selection::Selection, Result};
 use clap::Args;
-use cmfy::{Client, Result};
+use cmfy::{Client, Status};
+use colored::Colorize;
 
 use super::Run;
 
-/// Cancel currently running prompt
+/// Cancel running and/or pending prompts.
+///
+/// Without selection, cancels the currently running prompt.
+/// Otherwise, selected pending prompts are removed from the
+/// queue, and the running prompt is interrupted only if it
+/// is part of the selection.
+///
+/// Servers that predate targeted interrupts stop whatever is
+/// running instead, so the running prompt is checked again just
+/// before interrupting it. A prompt starting in between could
+/// still be interrupted on those servers.
 #[derive(Debug, Args)]
-pub struct Cancel;
+pub struct Cancel {
+    /// Select prompts by indices and/or uuids,
+    /// e.g. '1,2,3' or '4-5' or '1,3,4-6'
+    #[clap(action, default_value = None)]
+    selection: Option<Selection>,
+}
 
 impl Run for Cancel {
     async fn run(self, client: Client) -> Result<()> {
-        client.cancel_running_prompt().await
+        let Some(selection) = self.selection else {
+            client.cancel_running_prompt().await?;
+            return Ok(());
+        };
+
+        let selected = client
+            .queue()
+            .await?
+            .into_batch_entries()
+            .filter(|entry| selection.contains(&entry.inner))
+            .collect::<Vec<_>>();
+        if selected.is_empty() {
+            Err("no matching prompt found in queue")?;
+        }
+
+        let pending = selected
+            .iter()
+            .filter(|entry| matches!(entry.status, Status::Pending))
+            .map(|entry| entry.inner.uuid.as_str());
+        client.delete_from_queue(pending).await?;
+
+        // checked again just before interrupting, as the running prompt may
+        // have completed, or a selected one started, since the queue was fetched
+        let running = client
+            .queue()
+            .await?
+            .running
+            .into_iter()
+            .filter(|prompt| selection.contains(prompt))
+            .map(|prompt| prompt.uuid)
+            .collect::<Vec<_>>();
+        for prompt_id in &running {
+            client.interrupt_prompt(prompt_id).await?;
+        }
+
+        for entry in selected {
+            let prompt = entry.inner;
+            let completed =
+                matches!(entry.status, Status::Running) && !running.contains(&prompt.uuid);
+            let status = match completed {
+                true => "already completed".yellow(),
+                false => "cancelled".red(),
+            };
+            let index = format!("[{}] ", prompt.index.to_string().bright_blue());
+            println!("{:<15}{} ({})", index, prompt.uuid, status);
+        }
+        Ok(())
     }
 }
@@ -33,7 +33,7 @@ pub struct Capture {
 }
 
 impl Run for Capture {
-    async fn run(mut self, client: cmfy::Client) -> cmfy::Result<()> {
+    async fn run(mut self, client: cmfy::Client) -> crate::Result<()> {
         if self.queue || self.history {
             self.all = false;
         }
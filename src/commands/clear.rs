@@ -1,5 +1,6 @@
+use crate::{Error, Result};
 use clap::Args;
-use cmfy::{Client, Error, Result};
+use cmfy::Client;
 use humantime::Duration;
 use tokio::time::timeout;
 
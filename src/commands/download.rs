@@ -1,46 +1,78 @@
 use super::Run;
+use crate::{
+    selection::{select_history, Selection},
+    Result,
+};
 use clap::Args;
-use cmfy::{Client, Result};
-use itertools::Itertools;
-use std::io::Cursor;
+use cmfy::{
+    dto::{Channel, Preview, ViewOptions},
+    Client,
+};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::path::Path;
 
 /// Download images from completed prompts locally
 #[derive(Debug, Args)]
 pub struct Download {
-    /// Select a range of prompt indices,
+    /// Select prompts by indices and/or uuids,
     /// e.g. '1,2,3' or '4-5' or '1,3,4-6'
     #[clap(action, default_value = None)]
-    range: Option<String>,
+    selection: Option<Selection>,
+
+    /// Only consider the given number of most recent prompts
+    #[clap(short, long, action)]
+    last: Option<usize>,
 
     /// Remove prompts from history after
     #[clap(short, long, action, default_value_t = false)]
     clear: bool,
+
+    /// Download a compressed preview instead of the full image,
+    /// e.g. 'webp;90' or 'jpeg;80'
+    #[clap(long, action, value_name = "FORMAT(;QUALITY)")]
+    preview: Option<Preview>,
+
+    /// Only download the given channel(s): 'rgba', 'rgb' or 'a'
+    #[clap(long, action)]
+    channel: Option<Channel>,
 }
 
 impl Run for Download {
     async fn run(self, client: Client) -> Result<()> {
-        let history = client.history().await?;
-        let entries = if let Some(range) = self.range {
-            let range: Vec<i64> = range_parser::parse(&range)?;
-            history
-                .into_iter()
-                .filter(|entry| range.contains(&entry.prompt.index))
-                .collect_vec()
-        } else {
-            history.into_iter().collect_vec()
-        };
+        let entries = select_history(&client, self.selection.as_ref(), self.last).await?;
 
+        let options = ViewOptions {
+            preview: self.preview,
+            channel: self.channel,
+        };
+        let style = ProgressStyle::with_template("{msg} {bytes:>10}/{total_bytes:10}")?;
+        let multi = MultiProgress::new();
         let mut set = tokio::task::JoinSet::new();
         for entry in &entries {
             for image in entry.outputs.images() {
-                let url = client.url_for_image(image);
-                let filename = image.filename.clone();
+                let client = client.clone();
+                let image = image.clone();
+                let options = options.clone();
+                let multi = multi.clone();
+                let bar = multi.add(ProgressBar::new(0).with_style(style.clone()));
                 set.spawn(async move {
-                    let response = reqwest::get(url.clone()).await?;
-                    let mut file = std::fs::File::create(&filename)?;
-                    let mut content = Cursor::new(response.bytes().await?);
-                    std::io::copy(&mut content, &mut file)?;
-                    println!("{url} -> {filename}");
+                    let url = client.url_for_image_with(&image, &options)?;
+                    let filename = match &options.preview {
+                        Some(preview) => Path::new(&image.filename)
+                            .with_extension(preview.format.extension())
+                            .to_string_lossy()
+                            .into_owned(),
+                        None => image.filename.clone(),
+                    };
+                    let download = client.fetch_image(&image, &options).await?;
+                    bar.set_length(download.content_length().unwrap_or_default());
+                    bar.set_message(filename.clone());
+                    let mut file = tokio::fs::File::create(&filename).await?;
+                    download
+                        .write_to(&mut file, |written| bar.set_position(written))
+                        .await?;
+                    bar.finish_and_clear();
+                    multi.suspend(|| println!("{url} -> {filename}"));
                     Ok(())
                 });
             }
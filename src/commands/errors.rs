@@ -0,0 +1,120 @@
+use super::Run;
+use crate::{
+    io::{JsonWrite, Output},
+    selection::{select_history, Selection},
+    Result,
+};
+use clap::Args;
+use cmfy::{
+    dto::{self, websocket::ExecutionError},
+    Client,
+};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+
+const MAX_INPUT_LENGTH: usize = 120;
+
+/// Lists failed prompts from history with their execution error
+#[derive(Debug, Args)]
+pub struct Errors {
+    /// Select prompts by indices and/or uuids,
+    /// e.g. '1,2,3' or '4-5' or '1,3,4-6'
+    #[clap(action, default_value = None)]
+    selection: Option<Selection>,
+
+    /// Only consider the given number of most recent prompts
+    #[clap(short, long, action)]
+    last: Option<usize>,
+
+    /// Displays the full traceback of each error
+    #[clap(short, long, action, default_value_t = false)]
+    traceback: bool,
+
+    /// Outputs errors as JSON, tracebacks included
+    #[clap(long, short, action, default_value_t = false)]
+    json: bool,
+
+    /// Output path to store the JSON output (only used with --json)
+    #[clap(long, short, default_value = "-")]
+    output: Output,
+
+    /// Pretty prints the JSON output
+    #[clap(long, action, default_value_t = false)]
+    pretty: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorReport {
+    #[serde(serialize_with = "dto::serialize_number")]
+    index: f64,
+    #[serde(flatten)]
+    error: ExecutionError,
+}
+
+impl Run for Errors {
+    async fn run(mut self, client: Client) -> Result<()> {
+        let entries = select_history(&client, self.selection.as_ref(), self.last).await?;
+        let mut reports = vec![];
+        for entry in entries {
+            if let Some(error) = entry.execution_error()? {
+                let index = entry.prompt.index;
+                reports.push(ErrorReport { index, error });
+            }
+        }
+        reports.sort_by(|a, b| a.index.total_cmp(&b.index));
+
+        if self.json {
+            self.output.write_json(&reports, self.pretty)?;
+            return self.output.writeln();
+        }
+        for report in &reports {
+            self.display(report);
+        }
+        Ok(())
+    }
+}
+
+impl Errors {
+    fn display(&self, report: &ErrorReport) {
+        let error = &report.error;
+        let index = format!("[{}] ", report.index.to_string().bright_blue());
+        println!("{:<15}{} ({})", index, error.prompt_id, "failed".red());
+        println!(
+            "    {:<12}'{}' ({})",
+            "node", error.node_id, error.node_type
+        );
+        println!(
+            "    {:<12}{}: {}",
+            "exception",
+            error.exception_type.red(),
+            error.exception_message.trim()
+        );
+        if !error.current_inputs.is_empty() {
+            println!("    inputs");
+            for (name, value) in &error.current_inputs {
+                println!("        {}: {}", name.yellow(), format_input(value));
+            }
+        }
+        if self.traceback && !error.traceback.is_empty() {
+            println!("    traceback");
+            for line in error.traceback.iter().flat_map(|frame| frame.lines()) {
+                println!("        {}", line.dimmed());
+            }
+        }
+    }
+}
+
+/// Formats an input value, which the server stores as a list of
+/// formatted values, one per item of the batch.
+fn format_input(value: &Value) -> String {
+    let formatted = match value {
+        Value::Array(values) if values.len() == 1 => return format_input(&values[0]),
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    };
+    match formatted.char_indices().nth(MAX_INPUT_LENGTH) {
+        Some((end, _)) => format!("{}...", &formatted[..end]),
+        None => formatted,
+    }
+}
@@ -1,7 +1,8 @@
 use super::Run;
 use crate::io::{Input, JsonWrite, Output};
+use crate::Result;
 use clap::Args;
-use cmfy::{dto::PromptNodes, Client, Result};
+use cmfy::{dto::PromptNodes, Client};
 
 /// Extracts prompt from a PNG and outputs it as JSON.
 #[derive(Debug, Args)]
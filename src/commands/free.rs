@@ -0,0 +1,78 @@
+use super::{stats::describe_device, Run};
+use crate::Result;
+use clap::Args;
+use cmfy::{dto::SystemStats, Client};
+use colored::Colorize;
+use humansize::{make_format, BINARY};
+use std::time::{Duration, Instant};
+
+/// Releases memory held by the server.
+///
+/// Without flags, models are unloaded and cached memory is
+/// freed. The server only does so between prompts, so the
+/// command waits for the VRAM usage to change and displays
+/// how much each device got back.
+#[derive(Debug, Args)]
+pub struct Free {
+    /// Unloads models from memory
+    #[clap(long, action, default_value_t = false)]
+    unload_models: bool,
+
+    /// Frees memory cached by the server
+    #[clap(long, action, default_value_t = false)]
+    free_memory: bool,
+
+    /// Maximum time to wait for the memory to be released
+    #[clap(long, short, default_value = "5s")]
+    wait: humantime::Duration,
+}
+
+impl Run for Free {
+    async fn run(self, client: Client) -> Result<()> {
+        let (unload_models, free_memory) = if self.unload_models || self.free_memory {
+            (self.unload_models, self.free_memory)
+        } else {
+            (true, true)
+        };
+
+        let before = client.system_stats().await?;
+        client.free(unload_models, free_memory).await?;
+        let deadline = Instant::now() + Duration::from(self.wait);
+        let mut after = client.system_stats().await?;
+        while !vram_changed(&before, &after) && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            after = client.system_stats().await?;
+        }
+
+        let format_size = make_format(BINARY);
+        println!("{}", "devices".yellow());
+        for (index, device) in after.devices.iter().enumerate() {
+            let freed = before
+                .devices
+                .iter()
+                .find(|previous| previous.index == device.index && previous.name == device.name)
+                .map(|previous| device.vram_free as i64 - previous.vram_free as i64)
+                .unwrap_or_default();
+            let freed = match freed {
+                0 => "no change".dimmed(),
+                freed if freed > 0 => format!("+{} freed", format_size(freed as u64)).green(),
+                freed => format!("-{}", format_size(freed.unsigned_abs())).red(),
+            };
+            println!(
+                "    {:<8}: {} {}",
+                format!("[{}]", index),
+                describe_device(device),
+                freed
+            );
+        }
+        Ok(())
+    }
+}
+
+fn vram_changed(before: &SystemStats, after: &SystemStats) -> bool {
+    before
+        .devices
+        .iter()
+        .zip(&after.devices)
+        .any(|(before, after)| before.vram_free != after.vram_free)
+}
@@ -24,7 +24,7 @@ pub struct Get {
 }
 
 impl Run for Get {
-    async fn run(mut self, client: cmfy::Client) -> cmfy::Result<()> {
+    async fn run(mut self, client: cmfy::Client) -> crate::Result<()> {
         let response: serde_json::Value = client.get(self.route).await?;
         self.output.write_json(&response, self.pretty)
     }
@@ -15,7 +15,7 @@ pub struct History {
 }
 
 impl Run for History {
-    async fn run(self, client: Client) -> cmfy::Result<()> {
+    async fn run(self, client: Client) -> crate::Result<()> {
         if self.list {
             List::history().run(client.clone()).await?
         }
@@ -0,0 +1,192 @@
+use super::Run;
+use crate::{
+    io::{JsonWrite, Output},
+    Result,
+};
+use clap::Args;
+use cmfy::{
+    dto::{InputSpec, NodeInfo},
+    Client,
+};
+use colored::Colorize;
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+const MAX_DISPLAYED_OPTIONS: usize = 10;
+
+/// Browse node definitions available on the server.
+///
+/// Without argument, lists all node classes known to the
+/// server. When a class type is provided, displays its
+/// inputs, outputs and other information.
+#[derive(Debug, Args)]
+pub struct Info {
+    /// Class type of the node to display, e.g. "KSampler"
+    class_type: Option<String>,
+
+    /// Only list classes whose name or category contains this string
+    #[clap(long, short)]
+    filter: Option<String>,
+
+    /// Display all options of combo inputs
+    #[clap(long, short, action, default_value_t = false)]
+    all: bool,
+
+    /// Outputs the node definition(s) as JSON
+    #[clap(long, short, action, default_value_t = false)]
+    json: bool,
+
+    /// Output path to store the JSON output (only used with --json)
+    #[clap(long, short, default_value = "-")]
+    output: Output,
+
+    /// Pretty prints the JSON output
+    #[clap(long, action, default_value_t = false)]
+    pretty: bool,
+}
+
+impl Run for Info {
+    async fn run(mut self, client: Client) -> Result<()> {
+        if let Some(class_type) = &self.class_type {
+            let info = client.object_info_for(class_type).await?;
+            if self.json {
+                self.output.write_json(&info, self.pretty)?;
+            } else {
+                print_node_info(&info, self.all);
+            }
+        } else {
+            let mut object_info = client.object_info().await?;
+            if let Some(filter) = &self.filter {
+                let filter = filter.to_lowercase();
+                object_info.0.retain(|name, info| {
+                    name.to_lowercase().contains(&filter)
+                        || info.category.to_lowercase().contains(&filter)
+                });
+            }
+            if self.json {
+                self.output.write_json(&object_info, self.pretty)?;
+            } else {
+                for (name, info) in &object_info.0 {
+                    let marker = if info.output_node {
+                        " (output)".green().to_string()
+                    } else {
+                        String::new()
+                    };
+                    println!("{:<50}{}{}", name, info.category.dimmed(), marker);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn print_node_info(info: &NodeInfo, all: bool) {
+    println!("{}", info.name.yellow());
+    println!("    display name : {}", info.display_name);
+    println!("    category     : {}", info.category);
+    println!("    module       : {}", info.python_module);
+    println!(
+        "    output node  : {}",
+        if info.output_node { "yes" } else { "no" }
+    );
+    if !info.description.is_empty() {
+        println!("    description  : {}", info.description);
+    }
+
+    println!("    inputs");
+    for (section, inputs) in [
+        ("required", &info.input.required),
+        ("optional", &info.input.optional),
+        ("hidden", &info.input.hidden),
+    ] {
+        if inputs.is_empty() {
+            continue;
+        }
+        println!("        {section}");
+        let order = info.input_order.get(section);
+        let width = inputs.keys().map(String::len).max().unwrap_or_default();
+        for name in ordered_names(inputs, order) {
+            let spec = &inputs[name];
+            println!(
+                "            {:<width$} : {}",
+                name.cyan(),
+                describe_input(spec, all)
+            );
+        }
+    }
+
+    println!("    outputs");
+    for (index, output) in info.output.iter().enumerate() {
+        let name = info.output_name.get(index).map(String::as_str);
+        let is_list = info.output_is_list.get(index).copied().unwrap_or(false);
+        println!(
+            "        [{}] {}{}{}",
+            index.to_string().bright_blue(),
+            output,
+            name.map(|name| format!(" : {}", name.cyan()))
+                .unwrap_or_default(),
+            if is_list { " (list)" } else { "" }
+        );
+    }
+}
+
+fn ordered_names<'a>(
+    inputs: &'a BTreeMap<String, InputSpec>,
+    order: Option<&'a Vec<String>>,
+) -> Vec<&'a String> {
+    let mut names = order
+        .map(|order| {
+            order
+                .iter()
+                .filter(|name| inputs.contains_key(*name))
+                .collect_vec()
+        })
+        .unwrap_or_default();
+    names.extend(
+        inputs
+            .keys()
+            .filter(|name| !names.contains(name))
+            .collect_vec(),
+    );
+    names
+}
+
+fn describe_input(spec: &InputSpec, all: bool) -> String {
+    let mut description = spec.value_type.to_string();
+    if let Some(options) = spec.combo_options() {
+        let displayed = if all {
+            options.len()
+        } else {
+            options.len().min(MAX_DISPLAYED_OPTIONS)
+        };
+        let mut listed = options[..displayed].iter().map(format_value).join(", ");
+        if displayed < options.len() {
+            listed.push_str(&format!(", ... (+{} more)", options.len() - displayed));
+        }
+        description.push_str(&format!(" [{listed}]"));
+    }
+    let options = &spec.options;
+    let details = [
+        options
+            .default
+            .as_ref()
+            .map(|v| format!("default: {}", format_value(v))),
+        options.min.map(|v| format!("min: {v}")),
+        options.max.map(|v| format!("max: {v}")),
+        options.step.map(|v| format!("step: {v}")),
+    ]
+    .into_iter()
+    .flatten()
+    .join(", ");
+    if !details.is_empty() {
+        description.push_str(&format!(" ({})", details.dimmed()));
+    }
+    description
+}
+
+fn format_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(string) => string.clone(),
+        other => other.to_string(),
+    }
+}
@@ -1,13 +1,27 @@
 use super::Run;
 use clap::Args;
 
-use cmfy::{Client, Result, Status};
+use crate::{
+    eta::{format_eta, Estimator},
+    selection::{select_history, Selection, StatusFilter},
+    Result,
+};
+use cmfy::{
+    dto::{Preview, PromptBatchEntry, ViewOptions},
+    Client, Status,
+};
 use colored::Colorize;
+use std::collections::HashMap;
 
 /// List all prompts from history and queue
 #[derive(Debug, Args, Default)]
 #[clap(alias = "ls")]
 pub struct List {
+    /// Select prompts by indices and/or uuids,
+    /// e.g. '1,2,3' or '4-5' or '1,3,4-6'
+    #[clap(action, default_value = None)]
+    pub selection: Option<Selection>,
+
     /// Display prompts from history
     #[clap(short = 's', long, action, default_value_t = false)]
     pub history: bool,
@@ -23,6 +37,20 @@ pub struct List {
     /// Display URLs of output image for completed prompts
     #[clap(short, long, action, default_value_t = false)]
     pub images: bool,
+
+    /// Display URLs of compressed previews instead of full images,
+    /// e.g. 'webp;90' or 'jpeg;80' (only used with --images)
+    #[clap(long, action, value_name = "FORMAT(;QUALITY)")]
+    pub preview: Option<Preview>,
+
+    /// Only display the given number of most recent prompts from history
+    #[clap(short, long, action)]
+    pub last: Option<usize>,
+
+    /// Only display prompts with the given status(es),
+    /// e.g. 'failed' or 'completed,cancelled'
+    #[clap(long, action, value_name = "STATUS")]
+    pub status: Option<StatusFilter>,
 }
 
 impl List {
@@ -50,23 +78,65 @@ impl Run for List {
             self.queue = true;
         }
 
-        for entry in client
-            .collect_prompt_batch(self.history, self.queue)
-            .await?
-        {
+        let options = ViewOptions {
+            preview: self.preview,
+            ..ViewOptions::default()
+        };
+        let mut entries = vec![];
+        if self.history {
+            let history = select_history(&client, self.selection.as_ref(), self.last).await?;
+            entries.extend(history.into_iter().map(PromptBatchEntry::from));
+        }
+        let mut etas = HashMap::new();
+        let mut queue_eta = None;
+        if self.queue {
+            let queue = client.queue().await?;
+            if !queue.running.is_empty() || !queue.pending.is_empty() {
+                let estimator = Estimator::load(&client).await?;
+                let progress = queue
+                    .running
+                    .iter()
+                    .map(|prompt| estimator.progress_of(prompt))
+                    .collect::<Vec<_>>();
+                let running = queue.running.iter().zip(progress.iter().map(Some));
+                let queue_etas = estimator.queue_etas(running, &queue.pending);
+                queue_eta = queue_etas.last().and_then(|(_, eta)| *eta);
+                etas.extend(queue_etas);
+            }
+            entries.extend(queue.into_batch_entries().filter(|entry| {
+                self.selection
+                    .as_ref()
+                    .is_none_or(|selection| selection.contains(&entry.inner))
+            }));
+        }
+        if let Some(filter) = &self.status {
+            entries.retain(|entry| filter.contains(&entry.status));
+        }
+        entries.sort_by(|a, b| a.inner.index.total_cmp(&b.inner.index));
+
+        for entry in entries {
             let prompt = entry.inner;
             let index = format!("[{}] ", prompt.index.to_string().bright_blue());
             print!("{:<15}{} ({})", index, prompt.uuid, entry.status.colored());
+            if let Status::Failed(failure) = &entry.status {
+                print!(" {}", failure.to_string().red());
+            }
+            if let Some(Some(eta)) = etas.get(&prompt.uuid) {
+                print!(" {}", format!("eta {}", format_eta(*eta)).dimmed());
+            }
             if self.images {
                 if let Status::Completed(outputs) = entry.status {
                     if let Some(image) = outputs.images().next() {
-                        let url = client.url_for_image(image).to_string();
+                        let url = client.url_for_image_with(image, &options)?.to_string();
                         print!(" -> {}", url.cyan().underline());
                     }
                 }
             }
             println!();
         }
+        if let Some(eta) = queue_eta {
+            println!("queue completes in {}", format_eta(eta).yellow());
+        }
         Ok(())
     }
 }
@@ -1,7 +1,13 @@
 use super::Run;
 use crate::io::{JsonWrite, Output};
+use crate::Result;
 use clap::Args;
-use cmfy::{Client, Result};
+use cmfy::{
+    dto::websocket::{BinaryMessage, PreviewImage},
+    Client, Frame, ReconnectOptions,
+};
+use serde_json::{json, Value};
+use std::path::PathBuf;
 
 /// Displays JSON websocket messages from server
 #[derive(Debug, Clone, Args)]
@@ -9,17 +15,78 @@ pub struct Listen {
     /// Pretty prints the JSON output
     #[clap(long, action, default_value_t = false)]
     pretty: bool,
+
+    /// Reconnects when the connection to the server is lost,
+    /// emitting a message of type "reconnected"
+    #[clap(long, short, action, default_value_t = false)]
+    reconnect: bool,
+
+    /// Directory where preview images sent during sampling are
+    /// saved, named after the prompt id and step number
+    #[clap(long, action, value_name = "DIR")]
+    save_previews: Option<PathBuf>,
 }
 
 impl Run for Listen {
     async fn run(self, client: Client) -> Result<()> {
         let mut output = Output::default();
         let mut message_stream = client.listen().await?;
+        if self.reconnect {
+            message_stream = message_stream.with_reconnect(ReconnectOptions::default());
+        }
+        if let Some(directory) = &self.save_previews {
+            std::fs::create_dir_all(directory)?;
+        }
 
-        while let Some(value) = message_stream.next_json::<serde_json::Value>().await? {
-            output.write_json(&value, self.pretty)?;
-            output.writeln()?;
+        // previews without metadata belong to the last reported progress
+        let mut prompt_id = String::from("unknown");
+        let mut step = 0;
+        while let Some(frame) = message_stream.next_frame::<Value>().await? {
+            match frame {
+                Frame::Json(value) => {
+                    if let Some(id) = value.pointer("/data/prompt_id").and_then(Value::as_str) {
+                        prompt_id = id.to_string();
+                    }
+                    if value["type"] == "progress" {
+                        step = value["data"]["value"].as_u64().unwrap_or_default();
+                    }
+                    output.write_json(&value, self.pretty)?;
+                    output.writeln()?;
+                }
+                Frame::Binary(BinaryMessage::PreviewImage(preview)) => {
+                    if let Some(directory) = &self.save_previews {
+                        let prompt_id = preview
+                            .metadata
+                            .as_ref()
+                            .map(|metadata| metadata.prompt_id.as_str())
+                            .unwrap_or(&prompt_id);
+                        save_preview(directory, prompt_id, step, &preview)?;
+                    }
+                }
+                Frame::Binary(_) => {}
+                Frame::Reconnected(reconnected) => {
+                    let value = json!({
+                        "type": "reconnected",
+                        "data": { "attempts": reconnected.attempts }
+                    });
+                    output.write_json(&value, self.pretty)?;
+                    output.writeln()?;
+                }
+            }
         }
         Ok(())
     }
 }
+
+fn save_preview(
+    directory: &std::path::Path,
+    prompt_id: &str,
+    step: u64,
+    preview: &PreviewImage,
+) -> Result<()> {
+    let filename = format!("{prompt_id}-{step:04}.{}", preview.format.extension());
+    let path = directory.join(filename);
+    std::fs::write(&path, &preview.data)?;
+    eprintln!("{}", path.display());
+    Ok(())
+}
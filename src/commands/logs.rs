@@ -0,0 +1,218 @@
+use super::Run;
+use crate::Result;
+use clap::Args;
+use cmfy::{
+    dto::{websocket::Message, LogEntry},
+    Client, ReconnectOptions,
+};
+use colored::Colorize;
+use std::{
+    io::Write,
+    time::{Duration, SystemTime},
+};
+
+/// Displays the console output of the server.
+///
+/// Timestamps are the local time of the server, which is
+/// assumed to share the timezone of this machine for --since.
+#[derive(Debug, Args)]
+pub struct Logs {
+    /// Keeps streaming new output until interrupted,
+    /// reconnecting when the server restarts
+    #[clap(long, short, action, default_value_t = false)]
+    follow: bool,
+
+    /// Only displays output from the given duration, e.g. '10m' or '1h'
+    #[clap(long, short)]
+    since: Option<humantime::Duration>,
+}
+
+impl Run for Logs {
+    async fn run(self, client: Client) -> Result<()> {
+        let since = self.since.map(|since| since_now(*since)).transpose()?;
+        let mut printer = LogPrinter::new(since, std::io::stdout());
+        if !self.follow {
+            printer.print_all(&client.raw_logs().await?.entries)?;
+            printer.flush()?;
+            return Ok(());
+        }
+
+        // subscribing before fetching past output ensures nothing is missed
+        // in between, entries received twice are skipped by the printer
+        let mut message_stream = client
+            .listen()
+            .await?
+            .with_reconnect(ReconnectOptions::default());
+        client.subscribe_logs(true).await?;
+        printer.print_all(&client.raw_logs().await?.entries)?;
+        while let Some(message) = message_stream.next_message().await? {
+            match message {
+                Message::Logs(logs) => printer.print_all(&logs.data.entries)?,
+                Message::Reconnected(_) => {
+                    printer.flush()?;
+                    eprintln!("{}", "reconnected".yellow());
+                    client.subscribe_logs(true).await?;
+                    printer.print_all(&client.raw_logs().await?.entries)?;
+                }
+                _ => {}
+            }
+        }
+        printer.flush()?;
+        Ok(())
+    }
+}
+
+fn since_now(duration: Duration) -> Result<SystemTime> {
+    let since = SystemTime::now().checked_sub(duration).ok_or(format!(
+        "--since {} goes too far back in time",
+        humantime::format_duration(duration)
+    ))?;
+    Ok(since)
+}
+
+/// Reassembles console lines from log entries and prints them
+/// prefixed with the time their first entry was written.
+struct LogPrinter<W: Write> {
+    output: W,
+    since: Option<SystemTime>,
+    /// Time of the last entry printed, with the messages printed at that time
+    last: Option<(SystemTime, Vec<String>)>,
+    timestamp: String,
+    line: String,
+}
+
+impl<W: Write> LogPrinter<W> {
+    fn new(since: Option<SystemTime>, output: W) -> Self {
+        Self {
+            output,
+            since,
+            last: None,
+            timestamp: String::new(),
+            line: String::new(),
+        }
+    }
+
+    fn print_all(&mut self, entries: &[LogEntry]) -> std::io::Result<()> {
+        entries.iter().try_for_each(|entry| self.print(entry))
+    }
+
+    fn print(&mut self, entry: &LogEntry) -> std::io::Result<()> {
+        let time = entry.time();
+        if let Some(time) = time {
+            if self.since.is_some_and(|since| time < since) || self.seen(time, entry) {
+                return Ok(());
+            }
+            if self.last.as_ref().is_none_or(|(last, _)| *last != time) {
+                self.last = Some((time, vec![]));
+            }
+            if let Some((_, messages)) = &mut self.last {
+                messages.push(entry.message.clone());
+            }
+        }
+
+        for part in entry.message.split_inclusive('\n') {
+            if self.line.is_empty() {
+                self.timestamp = entry.timestamp.clone();
+            }
+            self.line.push_str(part);
+            if self.line.ends_with('\n') {
+                self.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the entry was already printed, entries being ordered by time.
+    fn seen(&self, time: SystemTime, entry: &LogEntry) -> bool {
+        match &self.last {
+            Some((last, messages)) => {
+                time < *last || (time == *last && messages.contains(&entry.message))
+            }
+            None => false,
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.line.is_empty() {
+            return Ok(());
+        }
+        // progress bars redraw the line after carriage returns
+        let line = self.line.trim_end_matches(['\r', '\n']);
+        let line = line.rsplit('\r').next().unwrap_or_default();
+        let timestamp = self.timestamp.split('.').next().unwrap_or_default();
+        writeln!(
+            self.output,
+            "{} {}",
+            timestamp.replace('T', " ").dimmed(),
+            line
+        )?;
+        self.line.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: &str, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: format!("2024-11-04T10:12:{timestamp}"),
+            message: message.to_string(),
+        }
+    }
+
+    fn printed(since: Option<SystemTime>, batches: &[&[LogEntry]]) -> String {
+        colored::control::set_override(false);
+        let mut printer = LogPrinter::new(since, vec![]);
+        for entries in batches {
+            printer.print_all(entries).unwrap();
+        }
+        printer.flush().unwrap();
+        String::from_utf8(printer.output).unwrap()
+    }
+
+    #[test]
+    fn lines_are_reassembled() {
+        let entries = [
+            entry("13.100", "loading "),
+            entry("13.200", "model\nstep 1\r"),
+            entry("14.000", "step 2\n"),
+            entry("15.000", "done"),
+        ];
+        assert_eq!(
+            printed(None, &[&entries]),
+            "2024-11-04 10:12:13 loading model\n\
+             2024-11-04 10:12:13 step 2\n\
+             2024-11-04 10:12:15 done\n"
+        );
+    }
+
+    #[test]
+    fn entries_printed_twice_are_skipped() {
+        let past = [
+            entry("13.000", "a\n"),
+            entry("14.000", "b\n"),
+            entry("14.000", "c\n"),
+        ];
+        let streamed = [entry("14.000", "c\n"), entry("14.000", "d\n")];
+        let refetched = [entry("13.000", "a\n"), entry("15.000", "e\n")];
+        let output = printed(None, &[&past, &streamed, &refetched]);
+        let messages = output.lines().map(|line| &line[20..]).collect::<Vec<_>>();
+        assert_eq!(messages, ["a", "b", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn entries_before_since_are_skipped() {
+        let since = entry("14.000", "").time();
+        let entries = [entry("13.999", "old\n"), entry("14.000", "new\n")];
+        assert_eq!(printed(since, &[&entries]), "2024-11-04 10:12:14 new\n");
+    }
+
+    #[test]
+    fn since_too_far_back() {
+        assert!(since_now(Duration::from_secs(60)).is_ok());
+        let error = since_now(Duration::MAX).unwrap_err();
+        assert!(error.to_string().contains("too far back"), "{error}");
+    }
+}
@@ -2,35 +2,56 @@ mod cancel;
 mod capture;
 mod clear;
 mod download;
+mod errors;
 mod extract;
+mod free;
 mod get;
 mod history;
+mod info;
 mod list;
 mod listen;
+mod logs;
+mod models;
 mod monitor;
 mod open;
 mod queue;
+mod report;
+mod settings;
 mod stats;
 mod submit;
+mod userdata;
+mod validate;
 mod view;
+mod wait;
 
 pub use cancel::Cancel;
 pub use capture::Capture;
 pub use clear::Clear;
 pub use download::Download;
+pub use errors::Errors;
 pub use extract::Extract;
+pub use free::Free;
 pub use get::Get;
 pub use history::History;
+pub use info::Info;
 pub use list::List;
 pub use listen::Listen;
+pub use logs::Logs;
+pub use models::Models;
 pub use monitor::Monitor;
 pub use open::Open;
 pub use queue::Queue;
+pub use report::Report;
+pub use settings::Settings;
 pub use stats::Stats;
 pub use submit::Submit;
+pub use userdata::Userdata;
+pub use validate::Validate;
 pub use view::View;
+pub use wait::Wait;
 
-use cmfy::{Client, Result};
+use crate::Result;
+use cmfy::Client;
 use enum_dispatch::enum_dispatch;
 
 #[enum_dispatch]
@@ -0,0 +1,162 @@
+use super::Run;
+use crate::{
+    io::{JsonWrite, Output},
+    Result,
+};
+use clap::Args;
+use cmfy::Client;
+use colored::Colorize;
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+const EXTENSIONS: &str = "extensions";
+
+/// Lists models available on the server.
+///
+/// Without argument, lists the content of all model folders
+/// (checkpoints, loras, vae, upscale_models, embeddings, ...).
+/// With --compare, displays the differences with the inventory
+/// of another server instead.
+#[derive(Debug, Args)]
+pub struct Models {
+    /// Model folder to list, e.g. "checkpoints" or "loras"
+    folder: Option<String>,
+
+    /// Only list models whose name contains this string
+    #[clap(long, short)]
+    filter: Option<String>,
+
+    /// Also lists the web extensions installed on the server
+    #[clap(long, short, action, default_value_t = false)]
+    extensions: bool,
+
+    /// Compares the inventory with the server at this URL,
+    /// '<' marks models only available on this server and
+    /// '>' models only available on the other one (using the
+    /// same headers, timeouts and retries, and the same
+    /// authentication if on the same origin, unless the URL
+    /// has its own credentials)
+    #[clap(long, short, value_name = "URL")]
+    compare: Option<String>,
+
+    /// Outputs the inventory (or the differences) as JSON
+    #[clap(long, short, action, default_value_t = false)]
+    json: bool,
+
+    /// Output path to store the JSON output (only used with --json)
+    #[clap(long, short, default_value = "-")]
+    output: Output,
+
+    /// Pretty prints the JSON output
+    #[clap(long, action, default_value_t = false)]
+    pretty: bool,
+}
+
+type Inventory = BTreeMap<String, Vec<String>>;
+
+#[derive(Debug, Default, Serialize)]
+struct Differences {
+    only_here: Vec<String>,
+    only_there: Vec<String>,
+}
+
+impl Run for Models {
+    async fn run(mut self, client: Client) -> Result<()> {
+        let inventory = self.inventory(&client).await?;
+        let Some(url) = &self.compare else {
+            if self.json {
+                self.output.write_json(&inventory, self.pretty)?;
+            } else if self.folder.is_some() {
+                inventory
+                    .values()
+                    .flatten()
+                    .for_each(|name| println!("{name}"));
+            } else {
+                for (folder, names) in &inventory {
+                    println!("{} ({})", folder.yellow(), names.len());
+                    names.iter().for_each(|name| println!("    {name}"));
+                }
+            }
+            return Ok(());
+        };
+
+        let other = client.with_base_url(url)?;
+        let other_inventory = self.inventory(&other).await?;
+        let differences = compare(&inventory, &other_inventory);
+        if self.json {
+            self.output.write_json(&differences, self.pretty)?;
+            return Ok(());
+        }
+        if differences.is_empty() {
+            println!("{}", "no differences".green());
+        }
+        for (folder, differences) in &differences {
+            println!("{}", folder.yellow());
+            for name in &differences.only_here {
+                println!("    {} {}", "<".red(), name);
+            }
+            for name in &differences.only_there {
+                println!("    {} {}", ">".green(), name);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Models {
+    async fn inventory(&self, client: &Client) -> Result<Inventory> {
+        let folders = match &self.folder {
+            Some(folder) => vec![folder.clone()],
+            None => client.model_folders().await?,
+        };
+        let mut inventory = Inventory::new();
+        for folder in folders {
+            let models = match client.models(&folder).await {
+                // a folder missing on one side of the comparison is empty
+                Err(cmfy::Error::HttpStatus { status, .. })
+                    if status == StatusCode::NOT_FOUND && self.compare.is_some() =>
+                {
+                    vec![]
+                }
+                models => models?,
+            };
+            inventory.insert(folder, models);
+        }
+        if self.extensions {
+            inventory.insert(EXTENSIONS.to_string(), client.extensions().await?);
+        }
+        if let Some(filter) = &self.filter {
+            let filter = filter.to_lowercase();
+            for names in inventory.values_mut() {
+                names.retain(|name| name.to_lowercase().contains(&filter));
+            }
+        }
+        Ok(inventory)
+    }
+}
+
+fn compare(here: &Inventory, there: &Inventory) -> BTreeMap<String, Differences> {
+    let empty = vec![];
+    let folders = here.keys().chain(there.keys()).collect::<BTreeSet<_>>();
+    folders
+        .into_iter()
+        .filter_map(|folder| {
+            let here = here.get(folder).unwrap_or(&empty);
+            let there = there.get(folder).unwrap_or(&empty);
+            let differences = Differences {
+                only_here: difference(here, there),
+                only_there: difference(there, here),
+            };
+            let different = !differences.only_here.is_empty() || !differences.only_there.is_empty();
+            different.then(|| (folder.clone(), differences))
+        })
+        .collect()
+}
+
+fn difference(left: &[String], right: &[String]) -> Vec<String> {
+    left.iter()
+        .filter(|name| !right.contains(name))
+        .cloned()
+        .collect()
+}
@@ -1,35 +1,76 @@
 use super::Run;
+use crate::{
+    eta::{format_eta, Estimator, RunningProgress, HISTORY_SAMPLE},
+    Result,
+};
 use clap::Args;
 use cmfy::{
-    dto::{websocket as ws, websocket::Message},
-    Client, Prompt, Result, Status,
+    dto::{websocket as ws, websocket::Message, HistoryLogEntry, PromptBatchEntry},
+    Client, Prompt, ReconnectOptions, Status,
 };
 use colored::Colorize;
+use futures_util::StreamExt;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use itertools::Itertools;
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+/// Number of recent history entries checked for prompts that
+/// completed between two refreshes.
+const NEWER_HISTORY_PAGE: usize = 8;
 
 /// Monitors the progress on ongoing prompts.
 #[derive(Debug, Clone, Args)]
-pub struct Monitor;
+pub struct Monitor {
+    /// Only display the given number of most recent prompts from history
+    #[clap(short, long, action)]
+    last: Option<usize>,
+
+    /// Reconnects when the connection to the server is lost
+    #[clap(long, short, action, default_value_t = false)]
+    reconnect: bool,
+}
 
 impl Run for Monitor {
     async fn run(self, client: Client) -> Result<()> {
         let mut stream = client.listen().await?;
-        let mut bars = AllStatusProgressBars::default();
-        let timeout = Duration::from_secs(1);
+        if self.reconnect {
+            stream = stream.with_reconnect(ReconnectOptions::default());
+        }
+        let mut bars = AllStatusProgressBars {
+            last: self.last,
+            ..Default::default()
+        };
+        let mut events =
+            stream
+                .into_events()
+                .of_kind(["status", "progress", "execution_start", "reconnected"]);
+        let mut ticks = tokio::time::interval(Duration::from_secs(1));
 
         loop {
-            match stream.next_json_with_timeout(timeout).await {
-                Ok(Ok(Some(message))) => bars.dispatch_message(&client, message).await?,
-                Ok(Ok(None)) => return Ok(()),
-                Ok(Err(error)) => return Err(error),
-                Err(_timeout) => bars.refresh(&client).await?,
+            tokio::select! {
+                event = events.next() => match event {
+                    Some(message) => bars.dispatch_message(&client, message?).await?,
+                    None => return Ok(()),
+                },
+                _ = ticks.tick() => match bars.refresh(&client).await {
+                    // the stream resyncs once the server is reachable again
+                    Err(error) if self.reconnect && is_transient(&error) => {}
+                    result => result?,
+                },
             }
         }
     }
 }
 
+fn is_transient(error: &crate::Error) -> bool {
+    error
+        .downcast_ref::<cmfy::Error>()
+        .is_some_and(cmfy::Error::is_transient)
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
 /// AllStatusProgressBars
 ///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -38,6 +79,15 @@ impl Run for Monitor {
 pub struct AllStatusProgressBars {
     pub multi: MultiProgress,
     pub by_id: HashMap<String, ProgressBar>,
+    pub finished: HashSet<String>,
+    pub history_loaded: bool,
+    /// Uuids of the history entries seen so far, displayed or not
+    pub known_history: HashSet<String>,
+    pub last: Option<usize>,
+    pub estimator: Estimator,
+    pub running: HashMap<String, RunningProgress>,
+    /// Bar below all prompts, displaying when the queue should complete
+    pub summary: Option<ProgressBar>,
 }
 
 struct AllStyles;
@@ -47,6 +97,9 @@ impl AllStyles {
         let template = "{prefix} {msg}";
         ProgressStyle::with_template(template).unwrap()
     }
+    pub fn summary() -> ProgressStyle {
+        ProgressStyle::with_template("{msg}").unwrap()
+    }
     pub fn with_message_and_timing() -> ProgressStyle {
         let template = "{prefix} {msg} -> [{elapsed_precise} < {duration_precise}]";
         ProgressStyle::with_template(template).unwrap()
@@ -63,15 +116,46 @@ impl AllStatusProgressBars {
         use Message::*;
         match message {
             Status(_) => self.refresh(client).await,
+            Reconnected(_) => {
+                // the server may have restarted, so everything is loaded again
+                for (_, bar) in self.by_id.drain() {
+                    bar.finish_and_clear();
+                    self.multi.remove(&bar);
+                }
+                if let Some(summary) = self.summary.take() {
+                    summary.finish_and_clear();
+                    self.multi.remove(&summary);
+                }
+                self.finished.clear();
+                self.running.clear();
+                self.history_loaded = false;
+                self.known_history.clear();
+                self.refresh(client).await
+            }
             Progress(contents) => {
-                let bar = self.get_progress_bar(&contents.data.prompt_id).unwrap();
+                let progress = &contents.data;
+                self.running
+                    .entry(progress.prompt_id.clone())
+                    .or_default()
+                    .update(progress.node.as_deref(), progress.value, progress.max);
+                // the prompt may not have been picked up by a refresh yet
+                let Some(bar) = self.get_progress_bar(&contents.data.prompt_id) else {
+                    return self.refresh(client).await;
+                };
                 bar.set_style(AllStyles::with_message_steps_and_timing());
                 bar.set_length(contents.data.max as u64);
                 bar.set_position(contents.data.value as u64);
                 Ok(())
             }
             ExecutionStart(contents) => {
-                let bar = self.get_progress_bar(&contents.data.prompt_id).unwrap();
+                let prompt_id = contents.data.prompt_id.clone();
+                let started = contents.data.timestamp.into();
+                self.running
+                    .insert(prompt_id, RunningProgress::since(started));
+                // the prompt may not have been picked up by a refresh yet
+                let Some(bar) = self.get_progress_bar(&contents.data.prompt_id) else {
+                    return self.refresh(client).await;
+                };
                 bar.reset_elapsed();
                 bar.reset_eta();
                 Ok(())
@@ -81,25 +165,73 @@ impl AllStatusProgressBars {
     }
 
     pub async fn refresh(&mut self, client: &Client) -> Result<()> {
-        let entries = client.collect_prompt_batch(true, true).await?;
-
-        let to_remove = self
-            .by_id
-            .keys()
-            .filter(|prompt_id| !entries.iter().any(|entry| entry.inner.uuid == **prompt_id))
-            .map(String::clone)
-            .collect_vec();
-
-        for prompt_id in to_remove {
-            let bar = self.by_id.remove(&prompt_id).unwrap();
-            bar.finish_and_clear();
-            self.multi.remove(&bar);
+        let mut entries = client.queue().await?.into_batch_entries().collect_vec();
+        if !self.history_loaded {
+            let history = match self.last {
+                Some(last) => client.history_page(last, None).await?,
+                None => client.history().await?,
+            };
+            // the history just loaded serves as reference durations when
+            // complete or large enough, sparing another request
+            let sufficient = self
+                .last
+                .is_none_or(|last| last >= HISTORY_SAMPLE || history.0.len() < last);
+            self.estimator = match sufficient {
+                true => Estimator::from_history(history.0.values()),
+                false => Estimator::load(client).await?,
+            };
+            self.known_history
+                .extend(history.0.values().map(|entry| entry.prompt.uuid.clone()));
+            if self.last.is_some() {
+                // older entries are out of the requested window, not new
+                let page = client.history_page(NEWER_HISTORY_PAGE, None).await?;
+                self.known_history.extend(page.0.into_keys());
+            }
+            entries.extend(history.into_batch_entries());
+            self.history_loaded = true;
+        } else {
+            // only prompts that left the queue since the last refresh are
+            // looked up, finished ones do not change anymore
+            let left = self
+                .by_id
+                .keys()
+                .filter(|prompt_id| !self.finished.contains(*prompt_id))
+                .filter(|prompt_id| !entries.iter().any(|entry| entry.inner.uuid == **prompt_id))
+                .map(String::clone)
+                .collect_vec();
+            for prompt_id in left {
+                self.running.remove(&prompt_id);
+                match client.history_for(&prompt_id).await? {
+                    Some(entry) => {
+                        self.estimator.add(&entry);
+                        entries.push(entry.into());
+                    }
+                    None => {
+                        let bar = self.by_id.remove(&prompt_id).unwrap();
+                        bar.finish_and_clear();
+                        self.multi.remove(&bar);
+                    }
+                }
+            }
+            for entry in self.newer_history(client).await? {
+                self.estimator.add(&entry);
+                entries.push(entry.into());
+            }
         }
+        entries.sort_by(|a, b| a.inner.index.total_cmp(&b.inner.index));
+        let etas = self.queue_etas(&entries);
 
         for entry in entries {
+            let eta = match etas.get(&entry.inner.uuid) {
+                Some(Some(eta)) => format!(" {}", format!("eta {}", format_eta(*eta)).dimmed()),
+                _ => String::new(),
+            };
             let bar = self.get_progress_bar(&entry.inner.uuid).unwrap_or_else(|| {
                 let prompt: &Prompt = &entry.inner;
-                let bar = self.multi.add(ProgressBar::new(0));
+                let bar = match &self.summary {
+                    Some(summary) => self.multi.insert_before(summary, ProgressBar::new(0)),
+                    None => self.multi.add(ProgressBar::new(0)),
+                };
                 let index = format!("[{}] ", prompt.index.to_string().bright_blue());
                 bar.set_prefix(format!("{:<15}{}", index, prompt.uuid));
                 self.by_id.insert(prompt.uuid.clone(), bar.clone());
@@ -112,7 +244,7 @@ impl AllStatusProgressBars {
                     bar.set_style(AllStyles::with_message());
                     bar.disable_steady_tick();
                     if let Some(image) = outputs.images().next() {
-                        let url = client.url_for_image(image);
+                        let url = client.url_for_image(image)?;
                         bar.set_message(format!(
                             "{:<20} -> {}",
                             colored_status,
@@ -122,11 +254,26 @@ impl AllStatusProgressBars {
                         bar.set_message(format!("{colored_status:<20}"));
                     }
                     bar.finish();
+                    self.finished.insert(entry.inner.uuid.clone());
+                }
+                Status::Failed(failure) => {
+                    bar.set_style(AllStyles::with_message());
+                    bar.disable_steady_tick();
+                    let failure = failure.to_string().red();
+                    bar.set_message(format!("{colored_status:<20} {failure}"));
+                    bar.finish();
+                    self.finished.insert(entry.inner.uuid.clone());
                 }
-                Status::Pending | Status::Cancelled => {
+                Status::Cancelled => {
                     bar.set_style(AllStyles::with_message());
                     bar.disable_steady_tick();
                     bar.set_message(format!("{colored_status:<20}"));
+                    self.finished.insert(entry.inner.uuid.clone());
+                }
+                Status::Pending => {
+                    bar.set_style(AllStyles::with_message());
+                    bar.disable_steady_tick();
+                    bar.set_message(format!("{colored_status:<20}{eta}"));
                 }
                 Status::Running => {
                     if bar.length().is_some() {
@@ -135,14 +282,87 @@ impl AllStatusProgressBars {
                         bar.set_style(AllStyles::with_message_and_timing());
                     }
                     bar.enable_steady_tick(Duration::from_secs(1));
-                    bar.set_message(format!("{colored_status:<20}"));
+                    bar.set_message(format!("{colored_status:<20}{eta}"));
                 }
             };
         }
 
+        // unknown as soon as one queued prompt cannot be estimated
+        let queue_eta = etas
+            .into_values()
+            .collect::<Option<Vec<_>>>()
+            .and_then(|etas| etas.into_iter().max());
+        match queue_eta {
+            Some(eta) => {
+                let multi = &self.multi;
+                let summary = self.summary.get_or_insert_with(|| {
+                    let summary = multi.add(ProgressBar::new(0));
+                    summary.set_style(AllStyles::summary());
+                    summary
+                });
+                summary.set_message(format!("queue completes in {}", format_eta(eta).yellow()));
+            }
+            None => {
+                if let Some(summary) = &self.summary {
+                    summary.set_message("");
+                }
+            }
+        }
+
         Ok(())
     }
 
+    /// Retrieves prompts that reached history without ever being seen in
+    /// the queue, e.g. cached prompts completing between two refreshes.
+    /// The page grows until it reaches entries seen before.
+    async fn newer_history(&mut self, client: &Client) -> Result<Vec<HistoryLogEntry>> {
+        let is_known = |bars: &Self, uuid: &String| {
+            bars.known_history.contains(uuid) || bars.by_id.contains_key(uuid)
+        };
+        let mut size = NEWER_HISTORY_PAGE;
+        let page = loop {
+            let page = client.history_page(size, None).await?;
+            if page.0.len() < size || page.0.keys().any(|uuid| is_known(self, uuid)) {
+                break page;
+            }
+            size *= 2;
+        };
+        let newer = page
+            .0
+            .values()
+            .filter(|entry| !is_known(self, &entry.prompt.uuid))
+            .cloned()
+            .collect_vec();
+        self.known_history.extend(page.0.into_keys());
+        Ok(newer)
+    }
+
+    /// Estimates when queued prompts complete, tracking the progress
+    /// of running prompts from when they started.
+    fn queue_etas(&mut self, entries: &[PromptBatchEntry]) -> HashMap<String, Option<Duration>> {
+        let running = entries
+            .iter()
+            .filter(|entry| matches!(entry.status, Status::Running))
+            .map(|entry| &entry.inner)
+            .collect_vec();
+        for prompt in &running {
+            self.running
+                .entry(prompt.uuid.clone())
+                .or_insert_with(|| self.estimator.progress_of(prompt));
+        }
+        let running = running
+            .into_iter()
+            .map(|prompt| (prompt, self.running.get(&prompt.uuid)));
+        let pending = entries
+            .iter()
+            .filter(|entry| matches!(entry.status, Status::Pending))
+            .map(|entry| &entry.inner);
+        self.estimator
+            .queue_etas(running, pending)
+            .into_iter()
+            .collect()
+    }
+
     pub fn get_progress_bar(&self, prompt_id: impl AsRef<str>) -> Option<ProgressBar> {
         self.by_id.get(prompt_id.as_ref()).cloned()
     }
@@ -1,6 +1,7 @@
 use super::Run;
+use crate::Result;
 use clap::Args;
-use cmfy::{Client, Result};
+use cmfy::Client;
 
 /// Open ComfyUI in a web browser.
 #[derive(Debug, Args)]
@@ -8,7 +9,7 @@ pub struct Open;
 
 impl Run for Open {
     async fn run(self, client: Client) -> Result<()> {
-        let url = client.base_url()?;
+        let url = client.base_url();
         open::that(url.as_str())?;
         Ok(())
     }
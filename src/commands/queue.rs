@@ -1,7 +1,10 @@
 use super::{List, Run};
-use clap::Args;
+use crate::selection::Selection;
+use clap::{ArgGroup, Args, Subcommand};
+use cmfy::dto::QueuePosition;
+use colored::Colorize;
 
-/// Lists and/or clears clear queue
+/// Lists, clears and/or reorders the queue
 #[derive(Debug, Args)]
 pub struct Queue {
     /// Lists all pending prompts from queue
@@ -11,10 +14,48 @@ pub struct Queue {
     /// Clears all pending prompts from queue
     #[clap(long, short, action, default_value_t = false)]
     clear: bool,
+
+    #[command(subcommand)]
+    command: Option<QueueCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+enum QueueCommand {
+    Move(Move),
+}
+
+/// Moves pending prompts within the queue.
+///
+/// Selected prompts are removed from the queue and submitted
+/// again at the requested position, keeping their uuid and
+/// extra data. Their indices change in the process.
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("position").required(true).args(["front", "back", "before"])))]
+struct Move {
+    /// Select prompts by indices and/or uuids,
+    /// e.g. '1,2,3' or '4-5' or '1,3,4-6'
+    #[clap(action)]
+    selection: Selection,
+
+    /// Moves the prompts to the front of the queue
+    #[clap(long, action)]
+    front: bool,
+
+    /// Moves the prompts to the back of the queue
+    #[clap(long, action)]
+    back: bool,
+
+    /// Moves the prompts right before the pending prompt
+    /// with the given index or uuid
+    #[clap(long, value_name = "INDEX|UUID")]
+    before: Option<String>,
 }
 
 impl Run for Queue {
-    async fn run(self, client: cmfy::Client) -> cmfy::Result<()> {
+    async fn run(self, client: cmfy::Client) -> crate::Result<()> {
+        if let Some(QueueCommand::Move(command)) = self.command {
+            command.run(&client).await?;
+        }
         if self.list {
             List::queue().run(client.clone()).await?;
         }
@@ -24,3 +65,41 @@ impl Run for Queue {
         Ok(())
     }
 }
+
+impl Move {
+    async fn run(self, client: &cmfy::Client) -> crate::Result<()> {
+        let pending = client.queue().await?.pending;
+        let selected = pending
+            .iter()
+            .filter(|prompt| self.selection.contains(prompt))
+            .map(|prompt| prompt.uuid.clone())
+            .collect::<Vec<_>>();
+        if selected.is_empty() {
+            Err("no matching pending prompt found in queue")?;
+        }
+
+        let position = match &self.before {
+            Some(target) => {
+                let prompt = pending
+                    .iter()
+                    .find(|prompt| prompt.uuid == *target || prompt.index.to_string() == *target)
+                    .ok_or(format!("no pending prompt '{target}' found in queue"))?;
+                if selected.contains(&prompt.uuid) {
+                    Err(format!(
+                        "cannot move prompts before themselves ('{target}')"
+                    ))?;
+                }
+                QueuePosition::Before(prompt.uuid.clone())
+            }
+            None if self.front => QueuePosition::Front,
+            None => QueuePosition::Back,
+        };
+
+        let responses = client.move_in_queue(&selected, position).await?;
+        for response in responses {
+            let index = format!("[{}] ", response.number.to_string().bright_blue());
+            println!("{:<15}{}", index, response.prompt_id);
+        }
+        Ok(())
+    }
+}
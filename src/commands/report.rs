@@ -0,0 +1,316 @@
+use super::Run;
+use crate::{
+    io::Output,
+    selection::{select_history, Selection},
+    Result,
+};
+use clap::Args;
+use cmfy::{
+    dto::{HistoryLogEntry, PromptBatchEntry},
+    Client,
+};
+use cmfy_nodes::{CheckpointLoaderSimple, EmptyLatentImage, KSampler};
+use colored::Colorize;
+use itertools::Itertools;
+use std::{collections::HashMap, io::Write, str::FromStr, time::Duration};
+
+/// Reports execution times of prompts from history.
+///
+/// Without --by, displays one row per prompt with its queue wait
+/// (when the server records submission times), wall time and
+/// number of cached and executed nodes. With --by, aggregates
+/// prompts sharing the same parameter, largest total time first.
+#[derive(Debug, Args)]
+pub struct Report {
+    /// Select prompts by indices and/or uuids,
+    /// e.g. '1,2,3' or '4-5' or '1,3,4-6'
+    #[clap(action, default_value = None)]
+    selection: Option<Selection>,
+
+    /// Only consider the given number of most recent prompts
+    #[clap(short, long, action)]
+    last: Option<usize>,
+
+    /// Aggregates prompts by 'checkpoint', 'resolution', 'steps' or 'sampler'
+    #[clap(long, short, action, value_name = "PARAMETER")]
+    by: Option<GroupBy>,
+
+    /// Outputs the report as CSV, with durations in seconds
+    #[clap(long, action, default_value_t = false)]
+    csv: bool,
+
+    /// Output path to store the CSV output (only used with --csv)
+    #[clap(long, short, default_value = "-")]
+    output: Output,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum GroupBy {
+    Checkpoint,
+    Resolution,
+    Steps,
+    Sampler,
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "checkpoint" => Ok(GroupBy::Checkpoint),
+            "resolution" => Ok(GroupBy::Resolution),
+            "steps" => Ok(GroupBy::Steps),
+            "sampler" => Ok(GroupBy::Sampler),
+            _ => Err(format!(
+                "unknown parameter '{s}', expected checkpoint, resolution, steps or sampler"
+            )),
+        }
+    }
+}
+
+/// Timing and parameters of a single prompt, parameters being
+/// unknown when the prompt lacks the node or wires the input.
+struct Row {
+    index: f64,
+    prompt_id: String,
+    status: String,
+    queue_wait: Option<Duration>,
+    wall_time: Option<Duration>,
+    cached_nodes: usize,
+    executed_nodes: usize,
+    checkpoint: Option<String>,
+    resolution: Option<String>,
+    steps: Option<u8>,
+    sampler: Option<String>,
+}
+
+struct Table {
+    header: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Run for Report {
+    async fn run(mut self, client: Client) -> Result<()> {
+        let entries = select_history(&client, self.selection.as_ref(), self.last).await?;
+        let rows = entries
+            .into_iter()
+            .map(Row::from)
+            .sorted_by(|a, b| a.index.total_cmp(&b.index))
+            .collect_vec();
+
+        let table = match self.by {
+            Some(by) => self.aggregate(&rows, by),
+            None => self.per_prompt(&rows),
+        };
+        if self.csv {
+            let mut writer = self.output.lock();
+            table.write_csv(&mut writer)?;
+            writer.flush()?;
+        } else {
+            table.print();
+            let total = rows.iter().filter_map(|row| row.wall_time).sum();
+            println!(
+                "{} prompt(s), {} total execution time",
+                rows.len(),
+                self.duration(Some(total))
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Report {
+    fn per_prompt(&self, rows: &[Row]) -> Table {
+        let header = vec![
+            "index",
+            "prompt_id",
+            "status",
+            "queue_wait",
+            "wall_time",
+            "cached",
+            "executed",
+            "checkpoint",
+            "resolution",
+            "steps",
+            "sampler",
+        ];
+        let rows = rows
+            .iter()
+            .map(|row| {
+                vec![
+                    row.index.to_string(),
+                    row.prompt_id.clone(),
+                    row.status.clone(),
+                    self.duration(row.queue_wait),
+                    self.duration(row.wall_time),
+                    row.cached_nodes.to_string(),
+                    row.executed_nodes.to_string(),
+                    self.unknown(row.checkpoint.clone()),
+                    self.unknown(row.resolution.clone()),
+                    self.unknown(row.steps),
+                    self.unknown(row.sampler.clone()),
+                ]
+            })
+            .collect();
+        Table { header, rows }
+    }
+
+    fn aggregate(&self, rows: &[Row], by: GroupBy) -> Table {
+        let mut groups = HashMap::<String, Vec<&Row>>::new();
+        for row in rows {
+            let key = match by {
+                GroupBy::Checkpoint => self.unknown(row.checkpoint.clone()),
+                GroupBy::Resolution => self.unknown(row.resolution.clone()),
+                GroupBy::Steps => self.unknown(row.steps),
+                GroupBy::Sampler => self.unknown(row.sampler.clone()),
+            };
+            groups.entry(key).or_default().push(row);
+        }
+
+        let header = vec![
+            by.name(),
+            "prompts",
+            "total",
+            "mean",
+            "min",
+            "max",
+            "mean_queue_wait",
+            "cached",
+            "executed",
+        ];
+        let rows = groups
+            .into_iter()
+            .map(|(key, rows)| {
+                let times = rows.iter().filter_map(|row| row.wall_time).collect_vec();
+                let waits = rows.iter().filter_map(|row| row.queue_wait).collect_vec();
+                let total: Duration = times.iter().sum();
+                (total, key, rows, times, waits)
+            })
+            .sorted_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)))
+            .map(|(total, key, rows, times, waits)| {
+                vec![
+                    key,
+                    rows.len().to_string(),
+                    self.duration(Some(total)),
+                    self.duration(mean(&times)),
+                    self.duration(times.iter().min().copied()),
+                    self.duration(times.iter().max().copied()),
+                    self.duration(mean(&waits)),
+                    rows.iter()
+                        .map(|row| row.cached_nodes)
+                        .sum::<usize>()
+                        .to_string(),
+                    rows.iter()
+                        .map(|row| row.executed_nodes)
+                        .sum::<usize>()
+                        .to_string(),
+                ]
+            })
+            .collect();
+        Table { header, rows }
+    }
+
+    fn unknown(&self, value: Option<impl ToString>) -> String {
+        match (value, self.csv) {
+            (Some(value), _) => value.to_string(),
+            (None, true) => String::new(),
+            (None, false) => "?".to_string(),
+        }
+    }
+
+    fn duration(&self, duration: Option<Duration>) -> String {
+        match (duration, self.csv) {
+            (Some(duration), true) => format!("{:.3}", duration.as_secs_f64()),
+            (Some(duration), false) => format!("{:.1}s", duration.as_secs_f64()),
+            (None, true) => String::new(),
+            (None, false) => "-".to_string(),
+        }
+    }
+}
+
+impl GroupBy {
+    fn name(&self) -> &'static str {
+        match self {
+            GroupBy::Checkpoint => "checkpoint",
+            GroupBy::Resolution => "resolution",
+            GroupBy::Steps => "steps",
+            GroupBy::Sampler => "sampler",
+        }
+    }
+}
+
+impl From<HistoryLogEntry> for Row {
+    fn from(entry: HistoryLogEntry) -> Self {
+        let timing = entry.timing();
+        let nodes = &entry.prompt.nodes;
+        let resolution = match (nodes.width(), nodes.height()) {
+            (Ok(width), Ok(height)) => Some(format!("{width}x{height}")),
+            _ => None,
+        };
+        let mut row = Row {
+            index: entry.prompt.index,
+            prompt_id: entry.prompt.uuid.clone(),
+            status: String::new(),
+            queue_wait: timing.queue_wait(),
+            wall_time: timing.wall_time(),
+            cached_nodes: timing.cached_nodes,
+            executed_nodes: timing.executed_nodes,
+            checkpoint: nodes.ckpt_name().ok(),
+            resolution,
+            steps: nodes.steps().ok(),
+            sampler: nodes.sampler_name().ok(),
+        };
+        row.status = PromptBatchEntry::from(entry).status.to_string();
+        row
+    }
+}
+
+impl Table {
+    fn print(&self) {
+        let widths = (0..self.header.len())
+            .map(|column| {
+                self.rows
+                    .iter()
+                    .map(|row| row[column].chars().count())
+                    .chain([self.header[column].len()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect_vec();
+        let line = |cells: Vec<String>| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .join("  ")
+        };
+        let header = self.header.iter().map(|name| name.to_string()).collect();
+        println!("{}", line(header).trim_end().yellow());
+        for row in &self.rows {
+            println!("{}", line(row.clone()).trim_end());
+        }
+    }
+
+    fn write_csv(&self, writer: &mut impl Write) -> Result<()> {
+        writeln!(writer, "{}", self.header.join(","))?;
+        for row in &self.rows {
+            writeln!(writer, "{}", row.iter().map(|cell| escape(cell)).join(","))?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+fn mean(durations: &[Duration]) -> Option<Duration> {
+    let count = u32::try_from(durations.len())
+        .ok()
+        .filter(|count| *count > 0)?;
+    Some(durations.iter().sum::<Duration>() / count)
+}
@@ -0,0 +1,136 @@
+use super::Run;
+use crate::{
+    io::{Input, JsonRead, JsonWrite, Output},
+    Result,
+};
+use clap::{Args, Subcommand};
+use cmfy::{dto, Client};
+use colored::Colorize;
+use serde_json::Value;
+
+/// Reads and writes settings of the server, e.g. to version
+/// a settings profile and apply it to another server
+#[derive(Debug, Args)]
+pub struct Settings {
+    #[command(subcommand)]
+    command: SettingsCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum SettingsCommand {
+    Get(Get),
+    Set(Set),
+    Export(Export),
+    Import(Import),
+}
+
+/// Displays the value of a setting, or all settings, as JSON
+#[derive(Debug, Args)]
+struct Get {
+    /// Setting id, e.g. 'Comfy.LinkRenderMode'
+    id: Option<String>,
+
+    /// Pretty prints the JSON output
+    #[clap(long, action, default_value_t = false)]
+    pretty: bool,
+}
+
+/// Sets the value of a setting
+#[derive(Debug, Args)]
+struct Set {
+    /// Setting id, e.g. 'Comfy.LinkRenderMode'
+    id: String,
+
+    /// New value, parsed as JSON if possible and
+    /// used as a string otherwise
+    value: String,
+}
+
+/// Exports all settings as JSON
+#[derive(Debug, Args)]
+struct Export {
+    /// Output path to store the settings
+    #[clap(long, short, default_value = "-")]
+    output: Output,
+}
+
+/// Applies settings previously exported, leaving
+/// settings absent from the input untouched
+#[derive(Debug, Args)]
+struct Import {
+    /// Input path to read the settings from
+    #[clap(default_value = "-")]
+    input: Input,
+
+    /// Only displays the settings that would change
+    #[clap(long, short = 'n', action, default_value_t = false)]
+    dry_run: bool,
+}
+
+impl Run for Settings {
+    async fn run(self, client: Client) -> Result<()> {
+        match self.command {
+            SettingsCommand::Get(command) => command.run(&client).await,
+            SettingsCommand::Set(command) => command.run(&client).await,
+            SettingsCommand::Export(command) => command.run(&client).await,
+            SettingsCommand::Import(command) => command.run(&client).await,
+        }
+    }
+}
+
+impl Get {
+    async fn run(self, client: &Client) -> Result<()> {
+        let mut output = Output::default();
+        match &self.id {
+            Some(id) => match client.setting(id).await? {
+                Some(value) => output.write_json(&value, self.pretty)?,
+                None => Err(format!("setting '{id}' is not set"))?,
+            },
+            None => output.write_json(&client.settings().await?, self.pretty)?,
+        }
+        output.writeln()
+    }
+}
+
+impl Set {
+    async fn run(self, client: &Client) -> Result<()> {
+        let value = serde_json::from_str(&self.value).unwrap_or(Value::String(self.value));
+        client.set_setting(&self.id, &value).await?;
+        Ok(())
+    }
+}
+
+impl Export {
+    async fn run(mut self, client: &Client) -> Result<()> {
+        // pretty printed and sorted by id, so that exports diff well
+        self.output.write_json(&client.settings().await?, true)?;
+        self.output.writeln()
+    }
+}
+
+impl Import {
+    async fn run(mut self, client: &Client) -> Result<()> {
+        let settings: dto::Settings = self.input.read_json()?;
+        let current = client.settings().await?;
+        let changes = dto::Settings(
+            settings
+                .0
+                .into_iter()
+                .filter(|(id, value)| current.0.get(id) != Some(value))
+                .collect(),
+        );
+        for id in changes.0.keys() {
+            let marker = match current.0.contains_key(id) {
+                true => "~".yellow(),
+                false => "+".green(),
+            };
+            println!("{marker} {id}");
+        }
+        if !self.dry_run && !changes.0.is_empty() {
+            client.update_settings(&changes).await?;
+        }
+        let verb = if self.dry_run { "to change" } else { "changed" };
+        println!("{} setting(s) {verb}", changes.0.len());
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use super::Run;
+use crate::Result;
 use clap::Args;
-use cmfy::{Client, Result};
+use cmfy::{dto::Device, Client};
 use colored::Colorize;
 use humansize::{make_format, BINARY};
 
@@ -17,7 +18,7 @@ impl Run for Stats {
 
         let stats = client.system_stats().await?;
         println!("{}", "server".yellow());
-        println!("    url         : {}", client.base_url()?);
+        println!("    url         : {}", client.base_url());
         println!("    os          : {}", stats.system.os);
         println!("    versions");
         println!(
@@ -37,16 +38,23 @@ impl Run for Stats {
         println!("        comfyui : {}", stats.system.comfyui_version);
         println!("        pytorch : {}", stats.system.pytorch_version);
         println!("    devices");
-        let format_size = make_format(BINARY);
         for (index, device) in stats.devices.iter().enumerate() {
             println!(
-                "        {:<8}: {} ({}/{})",
+                "        {:<8}: {}",
                 format!("[{}]", index),
-                device.name,
-                format_size(device.vram_free),
-                format_size(device.vram_total),
+                describe_device(device)
             );
         }
         Ok(())
     }
 }
+
+pub fn describe_device(device: &Device) -> String {
+    let format_size = make_format(BINARY);
+    format!(
+        "{} ({}/{})",
+        device.name,
+        format_size(device.vram_free),
+        format_size(device.vram_total),
+    )
+}
@@ -1,15 +1,27 @@
-use super::Run;
+use super::{wait::print_outcome, Run};
 use crate::io::{Input, JsonRead};
+use crate::Result;
 use clap::Args;
-use cmfy::{dto, Client, Result};
+use cmfy::{dto, dto::websocket::Message, Client, WaitOptions};
 use cmfy_nodes::{EmptyLatentImage, KSampler};
 use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
 use itertools::Itertools;
+use ring::digest::{digest, SHA256};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+const LOAD_IMAGE_CLASSES: [&str; 2] = ["LoadImage", "LoadImageMask"];
 
 /// Submits a batch of prompts to the server.
 ///
 /// Reads a batch of prompts from a JSON file and submits it
-/// to the server.
+/// to the server. Images referenced by 'LoadImage' nodes that
+/// point to local files are uploaded to the server first.
 #[derive(Debug, Args)]
 pub struct Submit {
     /// Input file containing the prompts in json format
@@ -35,12 +47,29 @@ pub struct Submit {
     /// will be submitted.
     #[clap(long, short = 'n', action, default_value_t = 1)]
     count: usize,
+
+    /// Subfolder of the server's input directory where local
+    /// images are uploaded.
+    #[clap(long, action)]
+    upload_subfolder: Option<String>,
+
+    /// Waits for each prompt to complete before submitting the
+    /// next one. Exits with status 3 if a prompt failed during
+    /// execution, 4 if it was interrupted and 5 on timeout.
+    #[clap(long, short, action, default_value_t = false)]
+    wait: bool,
+
+    /// Maximum time to wait for each prompt (only used with --wait)
+    #[clap(long, action)]
+    wait_timeout: Option<humantime::Duration>,
 }
 
 impl Run for Submit {
     async fn run(mut self, client: Client) -> Result<()> {
         let prompts: Vec<dto::PromptNodes> = self.input.read_json()?;
+        let mut uploader = LocalImageUploader::new(self.upload_subfolder.clone());
         for mut prompt in prompts {
+            uploader.rewrite(&client, &mut prompt).await?;
             if let Some(size) = &self.size {
                 let split = size.split("x").collect_vec();
                 if split.len() != 2 && split.len() != 3 {
@@ -64,7 +93,17 @@ impl Run for Submit {
                 if self.reseed {
                     prompt.set_seed(rand::random())?;
                 }
-                let response = client.submit(&prompt).await?;
+                if self.wait {
+                    self.submit_and_wait(&client, &prompt).await?;
+                    continue;
+                }
+                let response = match client.submit(&prompt).await {
+                    Err(cmfy::Error::PromptValidation(report)) => {
+                        print_validation_report(&report);
+                        Err("prompt failed validation")?
+                    }
+                    response => response?,
+                };
                 let index = format!("[{}] ", response.number.to_string().bright_blue());
                 println!("{:<15}{}", index, response.prompt_id);
             }
@@ -72,3 +111,175 @@ impl Run for Submit {
         Ok(())
     }
 }
+
+impl Submit {
+    async fn submit_and_wait(&self, client: &Client, prompt: &dto::PromptNodes) -> Result<()> {
+        let options = WaitOptions {
+            timeout: self.wait_timeout.map(Into::into),
+            ..WaitOptions::default()
+        };
+        let style =
+            ProgressStyle::with_template("{spinner} {msg} {pos:>2}/{len:2} [{elapsed_precise}]")?;
+        let bar = ProgressBar::new(0).with_style(style);
+        bar.enable_steady_tick(Duration::from_millis(100));
+        let mut prompt_id = String::new();
+        let outcome = client
+            .submit_and_wait_with(prompt, &options, |message| {
+                if let Some(id) = message.prompt_id() {
+                    prompt_id = id.to_string();
+                    bar.set_message(prompt_id.clone());
+                }
+                if let Message::Progress(contents) = message {
+                    bar.set_length(contents.data.max as u64);
+                    bar.set_position(contents.data.value as u64);
+                }
+            })
+            .await;
+        bar.finish_and_clear();
+        match outcome {
+            Err(cmfy::Error::PromptValidation(report)) => {
+                print_validation_report(&report);
+                Err("prompt failed validation")?
+            }
+            // failures are reported along with the prompt they belong to
+            Err(error) if prompt_id.is_empty() => Err(error)?,
+            outcome => {
+                print_outcome(client, &prompt_id, &outcome);
+                outcome?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn print_validation_report(report: &dto::PromptValidationReport) {
+    eprintln!("{}: {}", "error".red(), report.error.message);
+    if !report.error.details.is_empty() {
+        eprintln!("    {}", report.error.details);
+    }
+    for (node_id, node) in &report.node_errors {
+        let index = format!("[{}] ", node_id.bright_blue());
+        eprintln!("{:<15}{}", index, node.class_type.yellow());
+        for error in &node.errors {
+            let input_name = error.extra_info.input_name.as_deref().unwrap_or("-");
+            eprintln!(
+                "    {:<20}: {} ({})",
+                input_name.cyan(),
+                error.message,
+                error.type_.dimmed()
+            );
+            if !error.details.is_empty() {
+                eprintln!("    {:<20}  {}", "", error.details);
+            }
+        }
+    }
+}
+
+struct LocalImageUploader {
+    subfolder: Option<String>,
+    available: Option<Vec<String>>,
+    uploaded: HashMap<PathBuf, String>,
+}
+
+impl LocalImageUploader {
+    fn new(subfolder: Option<String>) -> Self {
+        Self {
+            subfolder,
+            available: None,
+            uploaded: HashMap::new(),
+        }
+    }
+
+    async fn rewrite(&mut self, client: &Client, prompt: &mut dto::PromptNodes) -> Result<()> {
+        for node in prompt.0.values_mut() {
+            if !LOAD_IMAGE_CLASSES.contains(&node.class_type.as_str()) {
+                continue;
+            }
+            let Some(path) = node.inputs.get("image").and_then(Value::as_str) else {
+                continue;
+            };
+            let path = PathBuf::from(path);
+            if !path.is_file() {
+                continue;
+            }
+            let name = self.upload(client, &path).await?;
+            node.inputs["image"] = Value::String(name);
+        }
+        Ok(())
+    }
+
+    async fn upload(&mut self, client: &Client, path: &Path) -> Result<String> {
+        if let Some(name) = self.uploaded.get(path) {
+            return Ok(name.clone());
+        }
+
+        let content = std::fs::read(path)?;
+        let hash = digest(&SHA256, &content);
+        let prefix = hash.as_ref()[..8]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .join("");
+        let file_name = path
+            .file_name()
+            .ok_or(format!("invalid image path '{}'", path.display()))?
+            .to_string_lossy();
+        let filename = format!("{prefix}-{file_name}");
+        let server_path = match &self.subfolder {
+            Some(subfolder) => format!("{subfolder}/{filename}"),
+            None => filename.clone(),
+        };
+
+        let name = if self.exists(client, &filename).await? {
+            server_path
+        } else {
+            let options = dto::UploadOptions {
+                subfolder: self.subfolder.clone(),
+                ..Default::default()
+            };
+            let response = client.upload_image(filename, content, &options).await?;
+            eprintln!("{} -> {}", path.display(), response.path());
+            response.path()
+        };
+        self.uploaded.insert(path.to_path_buf(), name.clone());
+        Ok(name)
+    }
+
+    /// Whether a file was already uploaded under the given name. The
+    /// options of 'LoadImage' only list the top level of the input
+    /// directory, files in subfolders are looked up directly.
+    async fn exists(&mut self, client: &Client, filename: &str) -> Result<bool> {
+        match &self.subfolder {
+            Some(subfolder) => {
+                let image = dto::Image {
+                    filename: filename.to_string(),
+                    subfolder: subfolder.clone(),
+                    _type: "input".to_string(),
+                };
+                Ok(client.image_exists(&image).await?)
+            }
+            None => Ok(self
+                .available(client)
+                .await?
+                .iter()
+                .any(|name| name == filename)),
+        }
+    }
+
+    async fn available(&mut self, client: &Client) -> Result<&Vec<String>> {
+        if self.available.is_none() {
+            let available = match client.object_info_for(LOAD_IMAGE_CLASSES[0]).await {
+                Ok(info) => info
+                    .input("image")
+                    .and_then(|spec| spec.combo_options())
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|option| option.as_str().map(String::from))
+                    .collect_vec(),
+                Err(cmfy::Error::ClassNotFound(_)) => vec![],
+                Err(error) => Err(error)?,
+            };
+            self.available = Some(available);
+        }
+        Ok(self.available.get_or_insert_default())
+    }
+}
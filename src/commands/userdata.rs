@@ -0,0 +1,429 @@
+use super::Run;
+use crate::{
+    io::{Input, Output},
+    Result,
+};
+use clap::{Args, Subcommand};
+use cmfy::{dto::UserdataFile, Client};
+use colored::Colorize;
+use humansize::{make_format, BINARY};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{Read, Write},
+    path::{Component, Path, PathBuf},
+    time::{Duration, UNIX_EPOCH},
+};
+
+/// Manages files stored in the user directory of the server,
+/// such as saved workflows
+#[derive(Debug, Args)]
+pub struct Userdata {
+    #[command(subcommand)]
+    command: UserdataCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum UserdataCommand {
+    List(List),
+    Get(Get),
+    Put(Put),
+    #[command(alias = "mv")]
+    Move(Move),
+    #[command(alias = "rm")]
+    Remove(Remove),
+    /// Uploads JSON files of a local directory to the server,
+    /// skipping files changed on the server since their last
+    /// transfer unless forced
+    Push(Sync),
+    /// Downloads files of the server to a local directory,
+    /// skipping files changed locally since their last
+    /// transfer unless forced
+    Pull(Sync),
+}
+
+/// Lists files of a directory, recursively
+#[derive(Debug, Args)]
+struct List {
+    /// Directory relative to the user directory
+    #[clap(default_value = WORKFLOWS)]
+    directory: String,
+}
+
+/// Outputs the content of a file
+#[derive(Debug, Args)]
+struct Get {
+    /// Path relative to the user directory, e.g. 'workflows/upscale.json'
+    path: String,
+
+    /// Output path to store the file content
+    #[clap(long, short, default_value = "-")]
+    output: Output,
+}
+
+/// Writes a file from local content
+#[derive(Debug, Args)]
+struct Put {
+    /// Path relative to the user directory, e.g. 'workflows/upscale.json'
+    path: String,
+
+    /// Input path to read the file content from
+    #[clap(default_value = "-")]
+    input: Input,
+
+    /// Overwrites the file if it already exists
+    #[clap(long, action, default_value_t = false)]
+    overwrite: bool,
+}
+
+/// Moves or renames a file
+#[derive(Debug, Args)]
+struct Move {
+    /// Path of the file to move, relative to the user directory
+    source: String,
+
+    /// New path of the file, relative to the user directory
+    destination: String,
+
+    /// Overwrites the destination if it already exists
+    #[clap(long, action, default_value_t = false)]
+    overwrite: bool,
+}
+
+/// Deletes files
+#[derive(Debug, Args)]
+struct Remove {
+    /// Paths relative to the user directory
+    #[clap(required = true)]
+    paths: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+struct Sync {
+    /// Local directory of workflows
+    directory: PathBuf,
+
+    /// Directory on the server, relative to the user directory
+    #[clap(long, default_value = WORKFLOWS)]
+    folder: String,
+
+    /// Only displays what would be transferred
+    #[clap(long, short = 'n', action, default_value_t = false)]
+    dry_run: bool,
+
+    /// Overwrites files changed at the destination, or on both sides
+    #[clap(long, short, action, default_value_t = false)]
+    force: bool,
+}
+
+const WORKFLOWS: &str = "workflows";
+
+/// File of the local directory keeping track of the last transfers
+const SYNC_STATE: &str = ".cmfy-sync";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    Push,
+    Pull,
+}
+
+#[derive(Debug, PartialEq)]
+enum Change {
+    /// Missing at the destination
+    Missing,
+    Unchanged,
+    /// Changed at the source only
+    Updated,
+    /// Changed at the destination only
+    Newer,
+    /// Changed on both sides
+    Conflict,
+}
+
+/// Modification times of a file on both sides right after its last
+/// transfer, telling which side changed since then
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Synced {
+    remote: f64,
+    local: f64,
+}
+
+/// Last transfers by server URL of the files
+type SyncState = BTreeMap<String, Synced>;
+
+impl Run for Userdata {
+    async fn run(self, client: Client) -> Result<()> {
+        match self.command {
+            UserdataCommand::List(command) => command.run(&client).await,
+            UserdataCommand::Get(command) => command.run(&client).await,
+            UserdataCommand::Put(command) => command.run(&client).await,
+            UserdataCommand::Move(command) => command.run(&client).await,
+            UserdataCommand::Remove(command) => command.run(&client).await,
+            UserdataCommand::Push(command) => command.run(&client, Direction::Push).await,
+            UserdataCommand::Pull(command) => command.run(&client, Direction::Pull).await,
+        }
+    }
+}
+
+impl List {
+    async fn run(self, client: &Client) -> Result<()> {
+        let format_size = make_format(BINARY);
+        let mut files = client.list_userdata(&self.directory).await?;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        for file in files {
+            let modified = UNIX_EPOCH + Duration::from_secs_f64(file.modified.max(0.0));
+            println!(
+                "{:<40} {:>10}  {}",
+                file.path,
+                format_size(file.size),
+                humantime::format_rfc3339_seconds(modified)
+                    .to_string()
+                    .dimmed()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Get {
+    async fn run(mut self, client: &Client) -> Result<()> {
+        let content = client.fetch_userdata(&self.path).await?;
+        let mut writer = self.output.lock();
+        writer.write_all(&content)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Put {
+    async fn run(mut self, client: &Client) -> Result<()> {
+        let mut content = vec![];
+        self.input.lock().read_to_end(&mut content)?;
+        let file = client
+            .put_userdata(&self.path, content, self.overwrite)
+            .await?;
+        println!("{}", file.path);
+        Ok(())
+    }
+}
+
+impl Move {
+    async fn run(self, client: &Client) -> Result<()> {
+        let file = client
+            .move_userdata(&self.source, &self.destination, self.overwrite)
+            .await?;
+        println!("{}", file.path);
+        Ok(())
+    }
+}
+
+impl Remove {
+    async fn run(self, client: &Client) -> Result<()> {
+        for path in &self.paths {
+            client.delete_userdata(path).await?;
+        }
+        Ok(())
+    }
+}
+
+impl Sync {
+    async fn run(self, client: &Client, direction: Direction) -> Result<()> {
+        let folder = self.folder.trim_matches('/');
+        let remote = client
+            .list_userdata(folder)
+            .await?
+            .into_iter()
+            .map(|file| (file.path.clone(), file))
+            .collect::<BTreeMap<_, _>>();
+        let local = local_files(&self.directory)?;
+        let paths = match direction {
+            Direction::Push => local.iter().collect::<Vec<_>>(),
+            Direction::Pull => remote.keys().collect::<Vec<_>>(),
+        };
+        // checked beforehand, not to pull part of the files only
+        let paths = paths
+            .into_iter()
+            .map(|path| Ok((path, local_path(&self.directory, path)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let state_path = self.directory.join(SYNC_STATE);
+        let mut state = load_state(&state_path)?;
+        let initial_state = state.clone();
+
+        let (mut transferred, mut unchanged, mut skipped, mut conflicts) = (0, 0, 0, 0);
+        for (path, local_path) in paths {
+            let remote_path = format!("{folder}/{path}");
+            let key = format!("{}{remote_path}", client.base_url());
+            let change = compare(
+                client,
+                direction,
+                &remote_path,
+                &local_path,
+                remote.get(path),
+                state.get(&key),
+            )
+            .await?;
+            let marker = match change {
+                Change::Unchanged => {
+                    unchanged += 1;
+                    let remote = remote[path].modified;
+                    let local = local_modified(&local_path)?;
+                    state.insert(key, Synced { remote, local });
+                    continue;
+                }
+                Change::Newer if !self.force => {
+                    skipped += 1;
+                    let side = match direction {
+                        Direction::Push => "(changed on the server)",
+                        Direction::Pull => "(changed locally)",
+                    };
+                    println!("{} {} {}", "=".yellow(), path, side.dimmed());
+                    continue;
+                }
+                Change::Conflict if !self.force => {
+                    conflicts += 1;
+                    println!("{} {} {}", "!".red(), path, "(conflict)".dimmed());
+                    continue;
+                }
+                Change::Newer | Change::Conflict => "~".yellow(),
+                Change::Updated => "~".green(),
+                Change::Missing => "+".green(),
+            };
+            println!("{marker} {path}");
+            transferred += 1;
+            if self.dry_run {
+                continue;
+            }
+            let overwrite = change != Change::Missing;
+            let remote = match direction {
+                Direction::Push => {
+                    let content = std::fs::read(&local_path)?;
+                    let file = client
+                        .put_userdata(&remote_path, content, overwrite)
+                        .await?;
+                    file.modified
+                }
+                Direction::Pull => {
+                    let content = client.fetch_userdata(&remote_path).await?;
+                    if let Some(parent) = local_path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(&local_path, &content)?;
+                    remote[path].modified
+                }
+            };
+            let local = local_modified(&local_path)?;
+            state.insert(key, Synced { remote, local });
+        }
+        if !self.dry_run && state != initial_state {
+            std::fs::write(&state_path, serde_json::to_vec_pretty(&state)?)?;
+        }
+
+        let verb = match (direction, self.dry_run) {
+            (Direction::Push, false) => "pushed",
+            (Direction::Pull, false) => "pulled",
+            (Direction::Push, true) => "to push",
+            (Direction::Pull, true) => "to pull",
+        };
+        println!("{transferred} file(s) {verb}, {unchanged} unchanged, {skipped} skipped");
+        if conflicts > 0 {
+            Err(format!(
+                "{conflicts} file(s) changed on both sides, use --force to overwrite them"
+            ))?;
+        }
+        Ok(())
+    }
+}
+
+async fn compare(
+    client: &Client,
+    direction: Direction,
+    remote_path: &str,
+    local_path: &Path,
+    remote: Option<&UserdataFile>,
+    synced: Option<&Synced>,
+) -> Result<Change> {
+    let (Some(remote), true) = (remote, local_path.is_file()) else {
+        return Ok(Change::Missing);
+    };
+    if std::fs::metadata(local_path)?.len() == remote.size {
+        let local = std::fs::read(local_path)?;
+        if local == client.fetch_userdata(remote_path).await? {
+            return Ok(Change::Unchanged);
+        }
+    }
+    let modified = local_modified(local_path)?;
+    let (local_changed, remote_changed) = match synced {
+        Some(synced) => (modified != synced.local, remote.modified != synced.remote),
+        // never transferred: the most recent side is assumed to be the changed one
+        None => (modified > remote.modified, modified <= remote.modified),
+    };
+    let (source_changed, destination_changed) = match direction {
+        Direction::Push => (local_changed, remote_changed),
+        Direction::Pull => (remote_changed, local_changed),
+    };
+    Ok(match (source_changed, destination_changed) {
+        (true, false) => Change::Updated,
+        (false, true) => Change::Newer,
+        // differing content without any recorded change is suspicious too
+        (true, true) | (false, false) => Change::Conflict,
+    })
+}
+
+/// Joins a path listed by the server to the local `directory`,
+/// rejecting paths that would escape it, e.g. '../x.json'.
+fn local_path(directory: &Path, path: &str) -> Result<PathBuf> {
+    let relative = Path::new(path);
+    let joined = directory.join(relative);
+    let normal = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if path.is_empty() || !normal || !joined.starts_with(directory) {
+        Err(format!(
+            "refusing to sync '{path}' outside of '{}'",
+            directory.display()
+        ))?;
+    }
+    Ok(joined)
+}
+
+fn local_modified(path: &Path) -> Result<f64> {
+    let modified = std::fs::metadata(path)?.modified()?;
+    Ok(modified.duration_since(UNIX_EPOCH)?.as_secs_f64())
+}
+
+fn load_state(path: &Path) -> Result<SyncState> {
+    if !path.is_file() {
+        return Ok(SyncState::default());
+    }
+    Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+}
+
+/// Lists JSON files below `directory` by their relative path, using
+/// slashes as separators like the server does.
+fn local_files(directory: &Path) -> Result<BTreeSet<String>> {
+    let mut files = BTreeSet::new();
+    if !directory.exists() {
+        return Ok(files);
+    }
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(current) = directories.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                directories.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                let relative = path
+                    .strip_prefix(directory)?
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.insert(relative);
+            }
+        }
+    }
+    Ok(files)
+}
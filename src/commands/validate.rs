@@ -0,0 +1,66 @@
+use super::Run;
+use crate::{
+    io::{Input, JsonRead},
+    Result,
+};
+use clap::Args;
+use cmfy::{
+    dto::{ObjectInfo, PromptNodes},
+    Client,
+};
+use colored::Colorize;
+
+/// Validates a batch of prompts against the node definitions.
+///
+/// Checks that all node classes exist, that required inputs
+/// are provided, that values are within bounds or among the
+/// available options, and that links connect compatible
+/// types. Exits with a non-zero status if any prompt is invalid.
+#[derive(Debug, Args)]
+pub struct Validate {
+    /// Input file containing the prompts in json format
+    #[clap(default_value = "-")]
+    input: Input,
+
+    /// JSON snapshot of the node definitions (as returned
+    /// by '/object_info'). If none is provided, the node
+    /// definitions are retrieved from the server.
+    #[clap(long, short)]
+    schema: Option<Input>,
+}
+
+impl Run for Validate {
+    async fn run(mut self, client: Client) -> Result<()> {
+        let prompts: Vec<PromptNodes> = self.input.read_json()?;
+        let schema: ObjectInfo = match &mut self.schema {
+            Some(schema) => schema.read_json()?,
+            None => client.object_info().await?,
+        };
+
+        let mut invalid = 0;
+        for (index, prompt) in prompts.iter().enumerate() {
+            let issues = cmfy::validate(prompt, &schema);
+            let index = format!("[{}] ", index.to_string().bright_blue());
+            if issues.is_empty() {
+                println!("{:<15}{}", index, "valid".green());
+                continue;
+            }
+            invalid += 1;
+            println!("{:<15}{}", index, "invalid".red());
+            for issue in issues {
+                let node = format!("[{}] ", issue.node_id.bright_blue());
+                println!(
+                    "    {:<15}{:<30}{}",
+                    node,
+                    issue.class_type.yellow(),
+                    issue.kind
+                );
+            }
+        }
+
+        if invalid > 0 {
+            Err(format!("{invalid} prompt(s) failed validation"))?;
+        }
+        Ok(())
+    }
+}
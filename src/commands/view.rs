@@ -1,39 +1,49 @@
+use crate::{
+    selection::{select_history, Selection},
+    Result,
+};
 use clap::Args;
-use cmfy::{Client, Result};
-use itertools::Itertools;
+use cmfy::{
+    dto::{Preview, ViewOptions},
+    Client,
+};
 
 use super::Run;
 
 /// Open images from completed prompts in a browser
 #[derive(Debug, Args)]
 pub struct View {
-    /// Select a range of prompt indices,
+    /// Select prompts by indices and/or uuids,
     /// e.g. '1,2,3' or '4-5' or '1,3,4-6'
     #[clap(action, default_value = None)]
-    range: Option<String>,
+    selection: Option<Selection>,
+
+    /// Only consider the given number of most recent prompts
+    #[clap(short, long, action)]
+    last: Option<usize>,
 
     /// Remove prompts from history after
     #[clap(short, long, action, default_value_t = false)]
     clear: bool,
+
+    /// Open a compressed preview instead of the full image,
+    /// e.g. 'webp;90' or 'jpeg;80'
+    #[clap(long, action, value_name = "FORMAT(;QUALITY)")]
+    preview: Option<Preview>,
 }
 
 impl Run for View {
     async fn run(self, client: Client) -> Result<()> {
-        let history = client.history().await?;
-        let entries = if let Some(range) = self.range {
-            let range: Vec<i64> = range_parser::parse(&range)?;
-            history
-                .into_iter()
-                .filter(|entry| range.contains(&entry.prompt.index))
-                .collect_vec()
-        } else {
-            history.into_iter().collect_vec()
-        };
+        let entries = select_history(&client, self.selection.as_ref(), self.last).await?;
 
+        let options = ViewOptions {
+            preview: self.preview,
+            ..ViewOptions::default()
+        };
         let mut set = tokio::task::JoinSet::new();
         for entry in &entries {
             for image in entry.outputs.images() {
-                let url = client.url_for_image(image);
+                let url = client.url_for_image_with(image, &options)?;
                 set.spawn(async move {
                     println!("{url}");
                     open::that(url.to_string())
@@ -0,0 +1,101 @@
+use super::Run;
+use crate::{
+    selection::{select_history, Selection},
+    Result,
+};
+use clap::Args;
+use cmfy::{dto::Outputs, Client, WaitOptions};
+use colored::Colorize;
+use itertools::Itertools;
+
+/// Waits for prompts to complete.
+///
+/// Exits with status 0 once all selected prompts completed,
+/// 3 if a prompt failed during execution, 4 if a prompt was
+/// interrupted and 5 if the timeout expired.
+#[derive(Debug, Args)]
+pub struct Wait {
+    /// Select prompts by indices and/or uuids,
+    /// e.g. '1,2,3' or '4-5' or '1,3,4-6', possibly
+    /// given as several values, e.g. '1 3 <uuid>'
+    #[clap(action, num_args(1..), required = true)]
+    selections: Vec<Selection>,
+
+    /// Maximum time to wait for each prompt, e.g. '30s' or '10m'
+    #[clap(long, action)]
+    wait_timeout: Option<humantime::Duration>,
+}
+
+impl Run for Wait {
+    async fn run(self, client: Client) -> Result<()> {
+        let selection: Selection = self.selections.into_iter().collect();
+        let queue = client.queue().await?;
+        let history = select_history(&client, Some(&selection), None).await?;
+        let prompts = queue
+            .running
+            .into_iter()
+            .chain(queue.pending)
+            .chain(history.into_iter().map(|entry| entry.prompt))
+            .filter(|prompt| selection.contains(prompt))
+            .unique_by(|prompt| prompt.uuid.clone())
+            .sorted_by(|a, b| a.index.total_cmp(&b.index))
+            .collect_vec();
+        if prompts.is_empty() {
+            Err("no matching prompt found in queue or history")?;
+        }
+
+        let options = WaitOptions {
+            timeout: self.wait_timeout.map(Into::into),
+            ..WaitOptions::default()
+        };
+        let mut set = tokio::task::JoinSet::new();
+        for prompt in prompts {
+            let client = client.clone();
+            let options = options.clone();
+            set.spawn(async move {
+                let outcome = client.wait_for(&prompt.uuid, &options).await;
+                let index = format!("[{}] ", prompt.index.to_string().bright_blue());
+                print_outcome(&client, &format!("{:<15}{}", index, prompt.uuid), &outcome);
+                (prompt.index, outcome)
+            });
+        }
+        let failure = set
+            .join_all()
+            .await
+            .into_iter()
+            .sorted_by(|(a, _), (b, _)| a.total_cmp(b))
+            .find_map(|(_, outcome)| outcome.err());
+        match failure {
+            Some(error) => Err(error)?,
+            None => Ok(()),
+        }
+    }
+}
+
+pub fn print_outcome(client: &Client, prefix: &str, outcome: &cmfy::Result<Outputs>) {
+    let status = match outcome {
+        Ok(_) => "completed".green(),
+        Err(cmfy::Error::ExecutionFailed(_)) => "failed".red(),
+        Err(cmfy::Error::Interrupted(_)) => "cancelled".red(),
+        Err(cmfy::Error::WaitTimeout { .. }) => "timed out".yellow(),
+        Err(_) => "error".red(),
+    };
+    // written at once, outcomes being printed from concurrent tasks
+    let mut line = format!("{prefix} ({status})");
+    match outcome {
+        Ok(outputs) => {
+            if let Some(url) = outputs
+                .images()
+                .next()
+                .and_then(|image| client.url_for_image(image).ok())
+            {
+                line += &format!(" -> {}", url.to_string().cyan().underline());
+            }
+        }
+        Err(cmfy::Error::ExecutionFailed(error)) => {
+            line += &format!(" {}: {}", error.node_type.yellow(), error.exception_message);
+        }
+        Err(_) => {}
+    }
+    println!("{line}");
+}
@@ -0,0 +1,20 @@
+use std::process::ExitCode;
+
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Exit code when a prompt failed during execution
+pub const EXIT_EXECUTION_FAILED: u8 = 3;
+/// Exit code when a prompt was interrupted
+pub const EXIT_INTERRUPTED: u8 = 4;
+/// Exit code when waiting for a prompt timed out
+pub const EXIT_TIMEOUT: u8 = 5;
+
+pub fn exit_code(error: &Error) -> ExitCode {
+    match error.downcast_ref::<cmfy::Error>() {
+        Some(cmfy::Error::ExecutionFailed(_)) => ExitCode::from(EXIT_EXECUTION_FAILED),
+        Some(cmfy::Error::Interrupted(_)) => ExitCode::from(EXIT_INTERRUPTED),
+        Some(cmfy::Error::WaitTimeout { .. }) => ExitCode::from(EXIT_TIMEOUT),
+        _ => ExitCode::FAILURE,
+    }
+}
@@ -0,0 +1,312 @@
+use cmfy::{dto::HistoryLogEntry, Client, Prompt};
+use cmfy_nodes::{EmptyLatentImage, KSampler};
+use itertools::Itertools;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Number of recent history entries used as reference durations.
+pub const HISTORY_SAMPLE: usize = 200;
+
+/// Estimates the run time of prompts from the durations of comparable
+/// completed prompts in history: same class types, resolution and steps
+/// first, then same class types only, then any prompt.
+#[derive(Debug, Clone, Default)]
+pub struct Estimator {
+    by_profile: HashMap<Profile, Vec<Duration>>,
+    by_classes: HashMap<Vec<String>, Vec<Duration>>,
+    all: Vec<Duration>,
+    /// End of the most recent prompt in history, whatever its outcome
+    last_ended: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Profile {
+    classes: Vec<String>,
+    resolution: Option<(usize, usize)>,
+    steps: Option<u8>,
+}
+
+impl Profile {
+    fn of(prompt: &Prompt) -> Self {
+        let nodes = &prompt.nodes;
+        Self {
+            classes: nodes
+                .0
+                .values()
+                .map(|node| node.class_type.clone())
+                .sorted()
+                .dedup()
+                .collect(),
+            resolution: nodes.width().ok().zip(nodes.height().ok()),
+            steps: nodes.steps().ok(),
+        }
+    }
+}
+
+impl Estimator {
+    pub async fn load(client: &Client) -> crate::Result<Self> {
+        let history = client.history_page(HISTORY_SAMPLE, None).await?;
+        Ok(Self::from_history(history.0.values()))
+    }
+
+    pub fn from_history<'a>(entries: impl IntoIterator<Item = &'a HistoryLogEntry>) -> Self {
+        let mut estimator = Self::default();
+        entries.into_iter().for_each(|entry| estimator.add(entry));
+        estimator
+    }
+
+    /// Records the duration of a prompt, unless it did not complete.
+    pub fn add(&mut self, entry: &HistoryLogEntry) {
+        let timing = entry.timing();
+        if let Some(ended) = timing.ended.map(SystemTime::from) {
+            self.last_ended = self.last_ended.max(Some(ended));
+        }
+        if !entry.status.completed || entry.failure().is_some() {
+            return;
+        }
+        let Some(duration) = timing.wall_time() else {
+            return;
+        };
+        let profile = Profile::of(&entry.prompt);
+        self.by_classes
+            .entry(profile.classes.clone())
+            .or_default()
+            .push(duration);
+        self.by_profile.entry(profile).or_default().push(duration);
+        self.all.push(duration);
+    }
+
+    pub fn estimate(&self, prompt: &Prompt) -> Option<Duration> {
+        let profile = Profile::of(prompt);
+        let durations = self
+            .by_profile
+            .get(&profile)
+            .or_else(|| self.by_classes.get(&profile.classes))
+            .unwrap_or(&self.all);
+        median(durations)
+    }
+
+    /// Progress of a prompt found running without having seen it start,
+    /// which happened once submitted and after the previous prompt ended.
+    pub fn progress_of(&self, prompt: &Prompt) -> RunningProgress {
+        let created = prompt.created().map(SystemTime::from);
+        match created.max(self.last_ended) {
+            Some(started) => RunningProgress::since(started),
+            None => RunningProgress::default(),
+        }
+    }
+
+    /// Estimates the time left before each prompt completes, running
+    /// prompts first then pending ones in queue order. An estimate is
+    /// missing once a prompt in front could not be estimated.
+    pub fn queue_etas<'a>(
+        &self,
+        running: impl IntoIterator<Item = (&'a Prompt, Option<&'a RunningProgress>)>,
+        pending: impl IntoIterator<Item = &'a Prompt>,
+    ) -> Vec<(String, Option<Duration>)> {
+        let running = running.into_iter().map(|(prompt, progress)| {
+            let remaining = self
+                .estimate(prompt)
+                .map(|estimate| progress.map_or(estimate, |progress| progress.remaining(estimate)));
+            (prompt, remaining)
+        });
+        let pending = pending
+            .into_iter()
+            .sorted_by(|a, b| a.index.total_cmp(&b.index))
+            .map(|prompt| (prompt, self.estimate(prompt)));
+
+        let mut eta = Some(Duration::ZERO);
+        running
+            .chain(pending)
+            .map(|(prompt, remaining)| {
+                eta = eta.zip(remaining).map(|(eta, remaining)| eta + remaining);
+                (prompt.uuid.clone(), eta)
+            })
+            .collect()
+    }
+}
+
+/// Live progress of a running prompt, from websocket messages.
+#[derive(Debug, Clone)]
+pub struct RunningProgress {
+    started: Instant,
+    node: Option<String>,
+    node_started: Instant,
+    /// Progress of the current node when it was first reported
+    node_start_value: usize,
+    value: usize,
+    max: usize,
+}
+
+impl Default for RunningProgress {
+    fn default() -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            node: None,
+            node_started: now,
+            node_start_value: 0,
+            value: 0,
+            max: 0,
+        }
+    }
+}
+
+impl RunningProgress {
+    pub fn since(started: SystemTime) -> Self {
+        let elapsed = started.elapsed().unwrap_or_default();
+        let now = Instant::now();
+        Self {
+            started: now.checked_sub(elapsed).unwrap_or(now),
+            ..Self::default()
+        }
+    }
+
+    pub fn update(&mut self, node: Option<&str>, value: usize, max: usize) {
+        if self.node.as_deref() != node || value < self.value {
+            self.node = node.map(String::from);
+            self.node_started = Instant::now();
+            self.node_start_value = value;
+        }
+        self.value = value;
+        self.max = max;
+    }
+
+    /// Time left given the estimated total run time, never less than
+    /// what the current rate of progress of the current node implies.
+    pub fn remaining(&self, estimate: Duration) -> Duration {
+        let remaining = estimate.saturating_sub(self.started.elapsed());
+        let done = self.value.saturating_sub(self.node_start_value);
+        if done == 0 || self.max <= self.value {
+            return remaining;
+        }
+        let left = (self.max - self.value) as f64 / done as f64;
+        let node_remaining = self.node_started.elapsed().mul_f64(left);
+        remaining.max(node_remaining)
+    }
+}
+
+fn median(durations: &[Duration]) -> Option<Duration> {
+    let sorted = durations.iter().sorted().collect_vec();
+    sorted.get(sorted.len() / 2).copied().copied()
+}
+
+/// Formats an estimate to the second, e.g. '~2m 13s'.
+pub fn format_eta(eta: Duration) -> String {
+    let eta = Duration::from_secs(eta.as_secs());
+    format!("~{}", humantime::format_duration(eta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn now_ms() -> i64 {
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH);
+        now.unwrap().as_millis() as i64
+    }
+
+    fn sampler(steps: u8) -> Value {
+        let sampler = json!({
+            "cfg": 7.0, "denoise": 1.0, "sampler_name": "euler",
+            "scheduler": "normal", "steps": steps, "seed": 42,
+        });
+        let latent = json!({ "batch_size": 1, "width": 512, "height": 512 });
+        json!({
+            "3": { "class_type": "KSampler", "inputs": sampler },
+            "5": { "class_type": "EmptyLatentImage", "inputs": latent },
+        })
+    }
+
+    fn loader() -> Value {
+        json!({ "4": { "class_type": "CheckpointLoaderSimple", "inputs": {} } })
+    }
+
+    fn prompt(nodes: Value, created: i64) -> Prompt {
+        serde_json::from_value(json!([1, "id", nodes, { "create_time": created }, []])).unwrap()
+    }
+
+    fn entry(nodes: Value, outcome: &str, started: i64, ended: i64) -> HistoryLogEntry {
+        let data = |timestamp| json!({ "prompt_id": "id", "timestamp": timestamp });
+        serde_json::from_value(json!({
+            "prompt": prompt(nodes, started),
+            "outputs": {},
+            "status": {
+                "status_str": if outcome == "execution_success" { "success" } else { "error" },
+                "completed": outcome == "execution_success",
+                "messages": [["execution_start", data(started)], [outcome, data(ended)]],
+            },
+            "meta": {},
+        }))
+        .unwrap()
+    }
+
+    fn completed(nodes: Value, seconds: i64) -> HistoryLogEntry {
+        entry(nodes, "execution_success", 0, seconds * 1000)
+    }
+
+    #[test]
+    fn median_of_durations() {
+        let seconds = |values: &[u64]| {
+            values
+                .iter()
+                .copied()
+                .map(Duration::from_secs)
+                .collect_vec()
+        };
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&seconds(&[3, 1, 2])), Some(Duration::from_secs(2)));
+        assert_eq!(
+            median(&seconds(&[4, 1, 3, 2])),
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn estimate_falls_back_to_classes_then_all() {
+        let history = [
+            completed(sampler(20), 10),
+            completed(sampler(20), 14),
+            completed(sampler(20), 12),
+            entry(sampler(20), "execution_error", 0, 1_000_000),
+            completed(sampler(30), 30),
+            completed(loader(), 100),
+            completed(loader(), 110),
+        ];
+        let estimator = Estimator::from_history(&history);
+        let estimate = |nodes| {
+            estimator
+                .estimate(&prompt(nodes, 0))
+                .map(|eta| eta.as_secs())
+        };
+        assert_eq!(estimate(sampler(20)), Some(12));
+        assert_eq!(estimate(sampler(40)), Some(14));
+        let other = json!({ "1": { "class_type": "SaveImage", "inputs": {} } });
+        assert_eq!(estimate(other), Some(30));
+        assert_eq!(Estimator::default().estimate(&prompt(loader(), 0)), None);
+    }
+
+    #[test]
+    fn running_progress_starts_after_previous_prompt() {
+        let now = now_ms();
+        let history = [entry(
+            loader(),
+            "execution_success",
+            now - 50_000,
+            now - 30_000,
+        )];
+        let estimator = Estimator::from_history(&history);
+        let estimate = Duration::from_secs(100);
+        let remaining = |created| {
+            let progress = estimator.progress_of(&prompt(loader(), created));
+            progress.remaining(estimate).as_secs_f64().round()
+        };
+        assert_eq!(remaining(now - 60_000), 70.0);
+        assert_eq!(remaining(now - 10_000), 90.0);
+        let progress = Estimator::default().progress_of(&prompt(loader(), now - 10_000));
+        assert_eq!(progress.remaining(estimate).as_secs_f64().round(), 90.0);
+    }
+}
@@ -1,4 +1,4 @@
-use cmfy::Result;
+use crate::Result;
 use serde::{de::DeserializeOwned, Serialize};
 use std::io::Write;
 
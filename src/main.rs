@@ -1,14 +1,20 @@
 mod commands;
+mod error;
+mod eta;
 mod io;
+mod selection;
 
 use clap::{
     builder::{styling::AnsiColor, Styles},
     Parser, Subcommand,
 };
-use cmfy::{Client, Result};
+use cmfy::{Auth, Client, ClientBuilder, RetryPolicy};
+use colored::Colorize;
 use commands::*;
 use enum_dispatch::enum_dispatch;
+use error::{Error, Result};
 use ring::digest::{digest, SHA256};
+use std::process::ExitCode;
 use uuid::Uuid;
 
 pub fn build_styles() -> Styles {
@@ -44,6 +50,46 @@ struct Cli {
     )]
     port: u32,
 
+    /// full base URL of the server, e.g. "https://example.com/comfy/"
+    /// (takes precedence over --hostname and --port)
+    #[arg(short, long, env = "COMFY_URL", value_name = "URL")]
+    url: Option<String>,
+
+    /// bearer token used to authenticate with the server
+    #[arg(
+        long,
+        env = "COMFY_TOKEN",
+        value_name = "TOKEN",
+        conflicts_with = "basic_auth"
+    )]
+    token: Option<String>,
+
+    /// credentials used to authenticate with the server (basic auth)
+    #[arg(long, env = "COMFY_BASIC_AUTH", value_name = "USER[:PASSWORD]")]
+    basic_auth: Option<String>,
+
+    /// extra header sent with every request, e.g. "X-Api-Key: secret"
+    /// (can be repeated)
+    #[arg(short = 'H', long = "header", value_name = "NAME:VALUE")]
+    headers: Vec<String>,
+
+    /// maximum time to wait for a connection to the server
+    #[arg(long, env = "COMFY_CONNECT_TIMEOUT", default_value = "10s")]
+    connect_timeout: humantime::Duration,
+
+    /// maximum time to wait for a request to complete
+    #[arg(long, env = "COMFY_TIMEOUT")]
+    timeout: Option<humantime::Duration>,
+
+    /// number of times a failing request is retried
+    #[arg(long, env = "COMFY_RETRIES", default_value_t = 3)]
+    retries: u32,
+
+    /// also retry failed prompt submissions (a prompt is only
+    /// resubmitted if it did not reach the server's queue)
+    #[arg(long, action, default_value_t = false)]
+    retry_submit: bool,
+
     /// command to execute
     #[command(subcommand)]
     command: Command,
@@ -67,6 +113,16 @@ enum Command {
     Extract(Extract),
     Monitor(Monitor),
     Download(Download),
+    Info(Info),
+    Validate(Validate),
+    Wait(Wait),
+    Free(Free),
+    Models(Models),
+    Userdata(Userdata),
+    Settings(Settings),
+    Logs(Logs),
+    Errors(Errors),
+    Report(Report),
 }
 
 fn compute_own_client_id() -> String {
@@ -77,9 +133,54 @@ fn compute_own_client_id() -> String {
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let args = Cli::parse();
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{}: {error}", "error".red());
+            error::exit_code(&error)
+        }
+    }
+}
+
+async fn run(args: Cli) -> Result<()> {
     let client_id = compute_own_client_id();
-    let client = Client::new(args.hostname, args.port, client_id);
+    let client = build_client(&args, client_id)?;
     args.command.run(client).await
 }
+
+fn build_client(args: &Cli, client_id: String) -> Result<Client> {
+    let mut builder = match &args.url {
+        Some(url) => ClientBuilder::new(url, client_id),
+        None => ClientBuilder::from_address(&args.hostname, args.port, client_id),
+    };
+    if let Some(token) = &args.token {
+        builder = builder.auth(Auth::Bearer(token.clone()));
+    }
+    if let Some(credentials) = &args.basic_auth {
+        let (username, password) = match credentials.split_once(':') {
+            Some((username, password)) => (username.to_string(), Some(password.to_string())),
+            None => (credentials.clone(), None),
+        };
+        builder = builder.auth(Auth::Basic { username, password });
+    }
+    for header in &args.headers {
+        let (name, value) = header
+            .split_once(':')
+            .ok_or(format!("header: could not parse '{header}'"))?;
+        builder = builder.header(name.trim(), value.trim());
+    }
+    if let Some(timeout) = args.timeout {
+        builder = builder.timeout(timeout.into());
+    }
+    let retry_policy = RetryPolicy {
+        max_retries: args.retries,
+        ..RetryPolicy::default()
+    };
+    let client = builder
+        .connect_timeout(args.connect_timeout.into())
+        .retry_policy(retry_policy)
+        .retry_submit(args.retry_submit)
+        .build()?;
+    Ok(client)
+}
@@ -0,0 +1,119 @@
+use cmfy::{dto::HistoryLogEntry, Client, Prompt, Status};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// A selection of prompts, given by index and/or uuid,
+/// e.g. '1,2,3' or '4-5' or '1,3,4-6' or a uuid. Prompts moved within
+/// the queue may have fractional indices, e.g. '2.5'.
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    pub indices: Vec<f64>,
+    pub uuids: Vec<String>,
+}
+
+impl Selection {
+    pub fn contains(&self, prompt: &Prompt) -> bool {
+        self.indices.contains(&prompt.index) || self.uuids.contains(&prompt.uuid)
+    }
+}
+
+impl FromIterator<Selection> for Selection {
+    fn from_iter<I: IntoIterator<Item = Selection>>(iter: I) -> Self {
+        let mut merged = Selection::default();
+        for selection in iter {
+            merged.indices.extend(selection.indices);
+            merged.uuids.extend(selection.uuids);
+        }
+        merged
+    }
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut selection = Selection::default();
+        for part in s.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            if let Ok(uuid) = Uuid::parse_str(part) {
+                selection.uuids.push(uuid.to_string());
+            } else if let Ok(index) = part.parse::<f64>() {
+                selection.indices.push(index);
+            } else {
+                let indices: Vec<i64> = range_parser::parse(part)
+                    .map_err(|_| format!("could not parse selection '{part}'"))?;
+                selection
+                    .indices
+                    .extend(indices.into_iter().map(|index| index as f64));
+            }
+        }
+        Ok(selection)
+    }
+}
+
+/// A filter on the status of prompts, e.g. 'failed' or 'completed,cancelled'.
+#[derive(Debug, Clone, Default)]
+pub struct StatusFilter(Vec<String>);
+
+const STATUSES: [&str; 5] = ["completed", "failed", "cancelled", "pending", "running"];
+
+impl StatusFilter {
+    pub fn contains<O>(&self, status: &Status<O>) -> bool {
+        let status = status.to_string();
+        self.0.contains(&status)
+    }
+}
+
+impl FromStr for StatusFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = StatusFilter::default();
+        for part in s.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            if !STATUSES.contains(&part) {
+                let expected = STATUSES.join(", ");
+                return Err(format!(
+                    "unknown status '{part}', expected one of {expected}"
+                ));
+            }
+            filter.0.push(part.to_string());
+        }
+        Ok(filter)
+    }
+}
+
+/// Retrieves the history entries of the selected prompts, keeping
+/// only the `last` most recent ones. Prompts selected only by uuid
+/// are retrieved one by one rather than through the whole history.
+pub async fn select_history(
+    client: &Client,
+    selection: Option<&Selection>,
+    last: Option<usize>,
+) -> crate::Result<Vec<HistoryLogEntry>> {
+    let mut entries = match selection {
+        Some(selection) if selection.indices.is_empty() => {
+            let mut entries = vec![];
+            for uuid in &selection.uuids {
+                entries.extend(client.history_for(uuid).await?);
+            }
+            entries
+        }
+        Some(selection) => client
+            .history()
+            .await?
+            .into_iter()
+            .filter(|entry| selection.contains(&entry.prompt))
+            .collect(),
+        None => {
+            let history = match last {
+                Some(last) => client.history_page(last, None).await?,
+                None => client.history().await?,
+            };
+            history.into_iter().collect()
+        }
+    };
+    entries.sort_by(|a, b| a.prompt.index.total_cmp(&b.prompt.index));
+    if let Some(last) = last {
+        entries.drain(..entries.len().saturating_sub(last));
+    }
+    Ok(entries)
+}